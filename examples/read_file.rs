@@ -15,4 +15,4 @@ fn main() -> anyhow::Result<()> {
     println!("{content_only}");
 
     Ok(())
-}
\ No newline at end of file
+}
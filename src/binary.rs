@@ -0,0 +1,247 @@
+use crate::constants::ZERO_BYTE;
+use crate::encoding::Encoding;
+
+/// The proportion of bytes that must be zero-bytes for content to look like BOM-less UTF-16
+const UTF16_MIN_ZERO_BYTE_RATIO: f64 = 0.3;
+
+/// The proportion of zero-bytes that must share the same parity for content to look like BOM-less UTF-16
+const UTF16_MIN_PARITY_RATIO: f64 = 0.9;
+
+/// The fewest bytes with strictly periodic zero-bytes that are taken to be UTF-16 rather than binary
+const UTF16_MIN_PERIODIC_LENGTH: usize = 8;
+
+/// The heuristic used to decide whether content without a BOM is binary.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum BinaryHeuristic {
+    /// Content is binary if it contains a zero-byte (same as Git), unless the zero-bytes are
+    /// strictly periodic like BOM-less UTF-16, i.e. every byte at an odd offset (or every byte at
+    /// an even offset) is a zero-byte, with none elsewhere, across at least 8 bytes
+    #[default]
+    NullByte,
+    /// Content is binary if it contains a zero-byte, or if the proportion of control bytes in it
+    /// is greater than `threshold` (from 0.0 to 1.0). Control bytes are the C0 controls and DEL,
+    /// other than tab, line feed, form feed, carriage return and escape.
+    ControlRatio { threshold: f32 },
+    /// Content is binary if the function returns true for it
+    Custom(fn(&[u8]) -> bool),
+}
+
+impl PartialEq for BinaryHeuristic {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BinaryHeuristic::NullByte, BinaryHeuristic::NullByte) => true,
+            (
+                BinaryHeuristic::ControlRatio { threshold: a },
+                BinaryHeuristic::ControlRatio { threshold: b },
+            ) => a == b,
+            // Functions are compared by address, so the same function may not always be equal
+            (BinaryHeuristic::Custom(a), BinaryHeuristic::Custom(b)) => {
+                std::ptr::fn_addr_eq(*a, *b)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The result of checking whether content is binary.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BinaryVerdict {
+    /// Whether the heuristic matched the scanned bytes
+    pub is_binary: bool,
+    /// Whether every byte was scanned. When this is false the content was only judged on a prefix
+    /// of it, so content that isn't binary may still contain binary data after that prefix.
+    pub scanned_fully: bool,
+}
+
+impl BinaryHeuristic {
+    /// Returns true if the heuristic matches the first `limit` bytes, or all of them if `limit` is `None`
+    pub fn is_binary(&self, bytes: &[u8], limit: Option<usize>) -> bool {
+        self.verdict(bytes, limit).is_binary
+    }
+
+    /// Checks the first `limit` bytes with the heuristic, or all of them if `limit` is `None`,
+    /// reporting whether that covered all of the bytes along with the result.
+    pub fn verdict(&self, bytes: &[u8], limit: Option<usize>) -> BinaryVerdict {
+        let window = &bytes[..bytes.len().min(limit.unwrap_or(usize::MAX))];
+        let is_binary = match self {
+            BinaryHeuristic::NullByte => is_binary(window, None),
+            BinaryHeuristic::ControlRatio { threshold } => {
+                let control_bytes = window.iter().filter(|b| is_control_byte(**b)).count();
+                is_binary(window, None)
+                    || (!window.is_empty()
+                        && control_bytes as f32 / window.len() as f32 > *threshold)
+            }
+            BinaryHeuristic::Custom(is_binary) => is_binary(window),
+        };
+
+        BinaryVerdict {
+            is_binary,
+            scanned_fully: window.len() == bytes.len(),
+        }
+    }
+}
+
+fn is_control_byte(byte: u8) -> bool {
+    (byte < 0x20 && !matches!(byte, b'\t' | b'\n' | 0x0C | b'\r' | 0x1B)) || byte == 0x7F
+}
+
+/// Returns true if it finds a zero-byte within the first `limit` bytes, or anywhere if `limit` is `None`.
+///
+/// Zero-bytes that are strictly periodic, as in BOM-less UTF-16 text in the Basic Latin and
+/// Latin-1 ranges, don't count. See [detect_periodic_utf16] for what that requires.
+pub fn is_binary(bytes: &[u8], limit: Option<usize>) -> bool {
+    let window = &bytes[..bytes.len().min(limit.unwrap_or(usize::MAX))];
+
+    window.contains(&ZERO_BYTE) && detect_periodic_utf16(window, None).is_none()
+}
+
+/// Returns the UTF-16 encoding the bytes are in if their zero-bytes are strictly periodic.
+/// Only the first `limit` bytes are checked, or all of them if `limit` is `None`.
+///
+/// This is a much stricter check than [detect_bomless_utf16], so it can be applied without opting
+/// in. The zero-bytes are strictly periodic when there are at least 8 checked bytes, and every
+/// byte at an odd offset (UTF-16LE) or even offset (UTF-16BE) is a zero-byte with none elsewhere.
+pub fn detect_periodic_utf16(bytes: &[u8], limit: Option<usize>) -> Option<Encoding> {
+    let window = &bytes[..bytes.len().min(limit.unwrap_or(usize::MAX))];
+    let is_periodic = |zero_parity: usize| {
+        window
+            .iter()
+            .enumerate()
+            .all(|(offset, byte)| (*byte == ZERO_BYTE) == (offset % 2 == zero_parity))
+    };
+
+    if window.len() < UTF16_MIN_PERIODIC_LENGTH {
+        None
+    } else if is_periodic(1) {
+        Some(Encoding::Utf16Le)
+    } else if is_periodic(0) {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Returns the UTF-16 encoding the bytes look like they are in, if they look like BOM-less UTF-16.
+/// Only the first `limit` bytes are checked, or all of them if `limit` is `None`.
+///
+/// Text in UTF-16 that is mostly made up of characters from the Basic Latin and Latin-1 ranges has
+/// a zero-byte in every other byte. Bytes are considered to be UTF-16 when:
+/// - more than 30% of the checked bytes are zero-bytes, and
+/// - at least 90% of those zero-bytes have the same parity (all at odd or all at even offsets).
+///
+/// Zero-bytes at odd offsets indicate UTF-16LE, and at even offsets indicate UTF-16BE.
+pub fn detect_bomless_utf16(bytes: &[u8], limit: Option<usize>) -> Option<Encoding> {
+    let window = &bytes[..bytes.len().min(limit.unwrap_or(usize::MAX))];
+    let window = &window[..window.len() - window.len() % 2];
+    let (even, odd) = window
+        .chunks(2)
+        .fold((0usize, 0usize), |(even, odd), unit| {
+            (
+                even + usize::from(unit[0] == ZERO_BYTE),
+                odd + usize::from(unit[1] == ZERO_BYTE),
+            )
+        });
+    let zero_bytes = (even + odd) as f64;
+
+    if window.is_empty() || zero_bytes / (window.len() as f64) <= UTF16_MIN_ZERO_BYTE_RATIO {
+        None
+    } else if odd as f64 / zero_bytes >= UTF16_MIN_PARITY_RATIO {
+        Some(Encoding::Utf16Le)
+    } else if even as f64 / zero_bytes >= UTF16_MIN_PARITY_RATIO {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::{
+        binary::{
+            detect_bomless_utf16, detect_periodic_utf16, is_binary, BinaryHeuristic, BinaryVerdict,
+        },
+        Encoding,
+    };
+
+    const UTF16LE_ASCII_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF16LE/ascii"
+    ));
+    const UTF16LE_UNICODE_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF16LE/unicode"
+    ));
+    const UTF16BE_ASCII_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF16BE/ascii"
+    ));
+
+    #[test_case(BinaryHeuristic::NullByte, b"Hello!\x01\x02", false; "Null byte without zero-bytes")]
+    #[test_case(BinaryHeuristic::NullByte, b"Hello!\0", true; "Null byte with a zero-byte")]
+    #[test_case(BinaryHeuristic::ControlRatio { threshold: 0.1 }, b"\x01\x01\x01\x01", true; "Control ratio of only control bytes")]
+    #[test_case(BinaryHeuristic::ControlRatio { threshold: 0.1 }, b"Hello!\x01", true; "Control ratio above the threshold")]
+    #[test_case(BinaryHeuristic::ControlRatio { threshold: 0.5 }, b"Hello!\x01", false; "Control ratio below the threshold")]
+    #[test_case(BinaryHeuristic::ControlRatio { threshold: 0.1 }, b"Hello!\r\n\tWorld!\x1B[0m\r\n", false; "Control ratio ignores whitespace and escape")]
+    #[test_case(BinaryHeuristic::ControlRatio { threshold: 1.0 }, b"Hello!\0", true; "Control ratio with a zero-byte")]
+    #[test_case(BinaryHeuristic::ControlRatio { threshold: 0.1 }, b"", false; "Control ratio of no content")]
+    #[test_case(BinaryHeuristic::Custom(|bytes| bytes.starts_with(b"%PDF")), b"%PDF-1.7", true; "Custom")]
+    #[test_case(BinaryHeuristic::NullByte, b"H\x00e\x00l\x00l\x00o\x00!\x00", false; "Null byte with periodic zero-bytes")]
+    fn heuristic(heuristic: BinaryHeuristic, bytes: &[u8], expected: bool) {
+        assert_eq!(heuristic.is_binary(bytes, None), expected);
+    }
+
+    #[test]
+    fn heuristic_limit() {
+        let heuristic = BinaryHeuristic::ControlRatio { threshold: 0.5 };
+
+        assert!(!heuristic.is_binary(b"Hello!\x01\x01\x01\x01\x01\x01\x01", Some(6)));
+    }
+
+    #[test_case(b"Hello!", Some(6), false, true; "Text at the limit")]
+    #[test_case(b"Hello!", Some(5), false, false; "Text past the limit")]
+    #[test_case(b"Hello!\0", Some(6), false, false; "Zero-byte past the limit")]
+    #[test_case(b"Hello!\0", None, true, true; "Unbounded scan")]
+    fn verdict(bytes: &[u8], limit: Option<usize>, is_binary: bool, scanned_fully: bool) {
+        let subject = BinaryHeuristic::NullByte.verdict(bytes, limit);
+
+        assert_eq!(
+            subject,
+            BinaryVerdict {
+                is_binary,
+                scanned_fully
+            }
+        );
+    }
+
+    #[test_case(b"H\x00i\x00", None, Some(Encoding::Utf16Le); "UTF-16LE")]
+    #[test_case(b"\x00H\x00i", None, Some(Encoding::Utf16Be); "UTF-16BE")]
+    #[test_case(b"H\x00e\x00l\x00l\x00o\x00,\x00 \x00W\x00o\x00r\x00l\x00d\x00\x00\x4E", None, Some(Encoding::Utf16Le); "UTF-16LE with a zero-byte at an even offset")]
+    #[test_case(b"H\x00\x00i", None, None; "Inconsistent parity")]
+    #[test_case(b"Hello!\x00", None, None; "Too few zero-bytes")]
+    #[test_case(b"Hello!H\x00i\x00", Some(6), None; "Zero-bytes after the limit")]
+    #[test_case(b"", None, None; "No content")]
+    fn bomless_utf16(bytes: &[u8], limit: Option<usize>, expected: Option<Encoding>) {
+        assert_eq!(detect_bomless_utf16(bytes, limit), expected);
+    }
+
+    #[test_case(&UTF16LE_ASCII_CONTENT[2..], false; "BOM-less UTF-16LE")]
+    #[test_case(&UTF16BE_ASCII_CONTENT[2..], false; "BOM-less UTF-16BE")]
+    #[test_case(b"H\x00i\x00", true; "Too short")]
+    #[test_case(b"H\x00e\x00l\x00\x00l\x00o\x00", true; "Irregular zero-bytes")]
+    #[test_case(b"H\x00e\x00l\x00l\x00o\x01!\x00", true; "Missing zero-byte")]
+    #[test_case(b"\x7FELF\x02\x01\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00", true; "Executable header")]
+    #[test_case(b"\x00\x00\x00\x00\x00\x00\x00\x00", true; "Only zero-bytes")]
+    fn binary_with_periodic_zero_bytes(bytes: &[u8], expected: bool) {
+        assert_eq!(is_binary(bytes, None), expected);
+    }
+
+    #[test_case(&UTF16LE_ASCII_CONTENT[2..], Some(Encoding::Utf16Le); "UTF-16LE")]
+    #[test_case(&UTF16BE_ASCII_CONTENT[2..], Some(Encoding::Utf16Be); "UTF-16BE")]
+    #[test_case(&UTF16LE_UNICODE_CONTENT[2..], None; "UTF-16LE outside Latin-1")]
+    #[test_case(b"Hello, World!", None; "No zero-bytes")]
+    fn periodic_utf16(bytes: &[u8], expected: Option<Encoding>) {
+        assert_eq!(detect_periodic_utf16(bytes, None), expected);
+    }
+}
@@ -1,6 +1,8 @@
 pub const UTF16BE_BOM: &[u8; 2] = b"\xFE\xFF";
 pub const UTF16LE_BOM: &[u8; 2] = b"\xFF\xFE";
 pub const UTF8_BOM: &[u8; 3] = b"\xEF\xBB\xBF";
+pub const UTF32BE_BOM: &[u8; 4] = b"\x00\x00\xFE\xFF";
+pub const UTF32LE_BOM: &[u8; 4] = b"\xFF\xFE\x00\x00";
 
 // Maximum buffer size (in 16-bit units) required for encoding a single UTF-16 character.
 pub const UTF16_BUFFER_SIZE: usize = 2;
@@ -8,5 +10,7 @@ pub const UTF16_BUFFER_SIZE: usize = 2;
 pub const ZERO_BYTE: u8 = 0x00;
 pub const BINARY_DETECTION_THRESHOLD: usize = 8_000;
 
-pub const UTF8_BOM_LENGTH: usize = 3;
-pub const UTF16_BOM_LENGTH: usize = 2;
+// The most bytes that are decoded, so the decoded content always fits in a String, which can hold
+// at most isize::MAX bytes. A byte can take up to 3 bytes once decoded (e.g. the euro sign in
+// Windows-1252), so this is about 715 MB on 32-bit targets, and more than fits in memory on 64-bit.
+pub const MAX_DECODE_INPUT_BYTES: usize = isize::MAX as usize / 3;
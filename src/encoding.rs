@@ -1,14 +1,308 @@
-use std::fmt::Display;
+use std::{fmt::Display, io::Read, str::FromStr};
 
-use crate::constants::{UTF16BE_BOM, UTF16LE_BOM, UTF16_BUFFER_SIZE, UTF8_BOM};
+use crate::binary::{detect_periodic_utf16, is_binary};
+use crate::constants::{
+    BINARY_DETECTION_THRESHOLD, UTF16BE_BOM, UTF16LE_BOM, UTF16_BUFFER_SIZE, UTF32BE_BOM,
+    UTF32LE_BOM, UTF8_BOM,
+};
+use crate::{cp037, utf7, windows1252};
+
+/// The BOMs that are recognised, in the order they must be checked.
+/// UTF-32LE must come before UTF-16LE, as its BOM starts with the UTF-16LE BOM.
+const BOMS: [(&[u8], Encoding); 5] = [
+    (UTF8_BOM, Encoding::Utf8Bom),
+    (UTF32BE_BOM, Encoding::Utf32Be),
+    (UTF32LE_BOM, Encoding::Utf32Le),
+    (UTF16BE_BOM, Encoding::Utf16Be),
+    (UTF16LE_BOM, Encoding::Utf16Le),
+];
+
+/// Every [Encoding] that is supported natively, in the order they are declared in
+const ALL: [Encoding; 9] = [
+    Encoding::Utf8,
+    Encoding::Utf8Bom,
+    Encoding::Utf16Be,
+    Encoding::Utf16Le,
+    Encoding::Utf32Be,
+    Encoding::Utf32Le,
+    Encoding::Utf7,
+    Encoding::Windows1252,
+    Encoding::Cp037,
+];
+
+/// The confidence [Encoding::detect_with_confidence] has in UTF-8 for ASCII content, which decodes
+/// the same in most encodings but isn't proof of UTF-8
+const ASCII_UTF8_CONFIDENCE: f32 = 0.9;
+
+/// The confidence [Encoding::detect_with_confidence] has in UTF-8 for content with non-ASCII bytes
+/// that are all in valid multi-byte sequences, which rarely happens by chance in other encodings
+const MULTI_BYTE_UTF8_CONFIDENCE: f32 = 0.99;
 
 /// Represents the supported encodings.
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Encoding {
     Utf8,
     Utf8Bom,
     Utf16Be,
     Utf16Le,
+    Utf32Be,
+    Utf32Le,
+    Utf7,
+    Windows1252,
+    /// EBCDIC code page 037, used by IBM mainframes. Like [Encoding::Windows1252] it has no BOM,
+    /// so it's only used when asked for, e.g. with [crate::TextData::try_from_bytes_with_fallback].
+    Cp037,
+    /// An encoding supported through `encoding_rs`, holding its WHATWG name, e.g. "Shift_JIS"
+    #[cfg(feature = "encoding_rs")]
+    Other(&'static str),
+}
+
+/// What to do with a [char] that can't be represented in the target [Encoding] when encoding.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Unmappable {
+    /// Fail with an [UnmappableCharError]
+    #[default]
+    Error,
+    /// Write the given byte in place of the character, e.g. `b'?'`
+    Replace(u8),
+    /// Leave the character out
+    Skip,
+}
+
+/// The error returned when a [char] can't be represented in the target [Encoding].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error("Character {character:?} cannot be represented in {encoding}")]
+pub struct UnmappableCharError {
+    pub character: char,
+    pub encoding: Encoding,
+}
+
+/// The error returned when parsing an [Encoding] from a name that isn't recognised.
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error("Unknown encoding name {name:?}")]
+pub struct ParseEncodingError {
+    pub name: String,
+}
+
+impl Encoding {
+    /// Every supported encoding, in a stable order, e.g. to list them for the user to pick from.
+    /// Encodings supported through `encoding_rs` aren't included, as there are too many of them;
+    /// see [Encoding::from_label] for those.
+    pub const fn all() -> &'static [Encoding] {
+        &ALL
+    }
+
+    /// Detect the encoding of the given bytes from their BOM, without decoding them.
+    ///
+    /// Returns `None` if the content is binary, and `Some(Encoding::Utf8)` if there is no BOM and
+    /// the content is not binary. Content with strictly periodic zero-bytes, like BOM-less UTF-16,
+    /// isn't UTF-8 either, so it's `None` too. This does not validate that the content after the BOM (or a
+    /// BOM-less UTF-8 body) is actually valid, only that a BOM or the binary heuristic matched.
+    pub fn detect(bytes: &[u8]) -> Option<Encoding> {
+        match Encoding::from_bom(bytes) {
+            Some((encoding, _)) => Some(encoding),
+            None if is_binary(bytes, Some(BINARY_DETECTION_THRESHOLD))
+                || detect_periodic_utf16(bytes, Some(BINARY_DETECTION_THRESHOLD)).is_some() =>
+            {
+                None
+            }
+            None => Some(Encoding::Utf8),
+        }
+    }
+
+    /// Detect the encoding of the given bytes as [Encoding::detect] does, along with a confidence
+    /// from 0.0 to 1.0 in the result, e.g. to decide whether to ask the user which encoding to use.
+    ///
+    /// The confidence is 1.0 when a BOM matched, or when the content is binary (`None`), as text
+    /// doesn't contain zero-bytes. For the UTF-8 fallback, it's 0.9 for content that is all ASCII, and for other content it's 0.99
+    /// scaled by the proportion of non-ASCII bytes that are part of valid UTF-8 sequences, so
+    /// content in a legacy encoding like Windows-1252 has a low confidence. As with
+    /// [Encoding::detect], only the first 8 thousand bytes are checked.
+    pub fn detect_with_confidence(bytes: &[u8]) -> (Option<Encoding>, f32) {
+        let window = &bytes[..bytes.len().min(BINARY_DETECTION_THRESHOLD)];
+        match Encoding::detect(bytes) {
+            Some(Encoding::Utf8) => (Some(Encoding::Utf8), utf8_confidence(window)),
+            result => (result, 1.0),
+        }
+    }
+
+    /// Check whether all of the bytes are valid UTF-8, including any BOM, which is U+FEFF in UTF-8.
+    ///
+    /// Unlike decoding with [crate::TextData::try_from], no BOM is detected or stripped, so bytes
+    /// starting with a UTF-16 or UTF-32 BOM are not valid, and zero-bytes are not rejected as binary.
+    pub fn is_valid_utf8(bytes: &[u8]) -> bool {
+        std::str::from_utf8(bytes).is_ok()
+    }
+
+    /// Detect the encoding of the content from the reader as [Encoding::detect] does, but only from
+    /// its first `sniff_bytes` bytes. No more than that is ever read, so large files can be sniffed
+    /// without loading them.
+    ///
+    /// Only the prefix is checked, so content that looks like text may still be binary, or fail to
+    /// decode, after it. The reader is left positioned after the bytes that were read.
+    pub fn detect_from_reader(
+        reader: &mut impl Read,
+        sniff_bytes: usize,
+    ) -> Result<Option<Encoding>, std::io::Error> {
+        let mut prefix = vec![];
+        reader.take(sniff_bytes as u64).read_to_end(&mut prefix)?;
+        Ok(Encoding::detect(&prefix))
+    }
+
+    /// Encode the string into bytes using this encoding, including the BOM if the encoding has one.
+    /// This is the inverse of decoding, e.g. with [crate::TextData::try_from_bytes_with_fallback].
+    ///
+    /// UnmappableCharError will be returned for [Encoding::Windows1252] and [Encoding::Cp037] if the
+    /// string contains a character that can't be represented in it.
+    pub fn encode(&self, s: &str) -> Result<Vec<u8>, UnmappableCharError> {
+        Ok(match self {
+            Encoding::Utf8 => s.as_bytes().to_vec(),
+            Encoding::Utf8Bom => to_utf8_bom(s),
+            Encoding::Utf16Be => to_utf16_be(s),
+            Encoding::Utf16Le => to_utf16_le(s),
+            Encoding::Utf32Be => to_utf32_be(s),
+            Encoding::Utf32Le => to_utf32_le(s),
+            Encoding::Utf7 => utf7::encode(s),
+            Encoding::Windows1252 => to_windows_1252(s)?,
+            Encoding::Cp037 => to_cp037(s)?,
+            #[cfg(feature = "encoding_rs")]
+            Encoding::Other(name) => to_other(s, name)?,
+        })
+    }
+
+    /// Returns the encoding with the given WHATWG label (e.g. "shift_jis"), if `encoding_rs`
+    /// supports it. Encodings that are supported natively are returned as their own variant, and
+    /// the rest as [Encoding::Other]. The "replacement" encoding isn't supported, as nothing can be
+    /// decoded with it.
+    #[cfg(feature = "encoding_rs")]
+    pub fn from_label(label: &str) -> Option<Encoding> {
+        let encoding = encoding_rs::Encoding::for_label(label.as_bytes())?;
+        match encoding {
+            _ if encoding == encoding_rs::UTF_8 => Some(Encoding::Utf8),
+            _ if encoding == encoding_rs::UTF_16BE => Some(Encoding::Utf16Be),
+            _ if encoding == encoding_rs::UTF_16LE => Some(Encoding::Utf16Le),
+            _ if encoding == encoding_rs::WINDOWS_1252 => Some(Encoding::Windows1252),
+            _ if encoding == encoding_rs::REPLACEMENT => None,
+            _ => Some(Encoding::Other(encoding.name())),
+        }
+    }
+
+    /// Whether the code units of this encoding are big-endian, or `None` if they are single bytes,
+    /// so have no byte order.
+    pub fn is_big_endian(&self) -> Option<bool> {
+        match self {
+            Encoding::Utf16Be | Encoding::Utf32Be => Some(true),
+            Encoding::Utf16Le | Encoding::Utf32Le => Some(false),
+            _ => None,
+        }
+    }
+
+    /// The number of bytes in a code unit of this encoding, e.g. 2 for UTF-16. A [char] takes up
+    /// one or more code units. Encodings supported through `encoding_rs` are all byte-oriented.
+    pub fn code_unit_bytes(&self) -> usize {
+        match self {
+            Encoding::Utf16Be | Encoding::Utf16Le => 2,
+            Encoding::Utf32Be | Encoding::Utf32Le => 4,
+            _ => 1,
+        }
+    }
+
+    /// Whether content in this encoding is written with a BOM
+    pub fn has_bom(&self) -> bool {
+        self.bom().is_some()
+    }
+
+    /// The name of this encoding in the IANA charset registry, e.g. "UTF-16LE", for use where a
+    /// standard charset name is expected, like a Content-Type header. Unlike the [Display] form,
+    /// it doesn't say whether there is a BOM, so [Encoding::Utf8Bom] is "UTF-8".
+    /// Encodings supported through `encoding_rs` use their WHATWG name.
+    pub fn iana_name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 | Encoding::Utf8Bom => "UTF-8",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf32Be => "UTF-32BE",
+            Encoding::Utf32Le => "UTF-32LE",
+            Encoding::Utf7 => "UTF-7",
+            Encoding::Windows1252 => "windows-1252",
+            Encoding::Cp037 => "IBM037",
+            #[cfg(feature = "encoding_rs")]
+            Encoding::Other(name) => name,
+        }
+    }
+
+    /// Returns the encoding whose BOM the bytes start with, along with the length of that BOM
+    pub(crate) fn from_bom(bytes: &[u8]) -> Option<(Encoding, usize)> {
+        BOMS.iter()
+            .find(|(bom, _)| bytes.starts_with(bom))
+            .map(|(bom, encoding)| (*encoding, bom.len()))
+    }
+
+    /// Returns the encoding whose BOM the bytes are the start of, if they are a part of a BOM and
+    /// nothing else, e.g. a file that was cut off part way through its BOM. The encoding with the
+    /// shortest BOM is returned if the bytes are the start of more than one, so `\xFF` is UTF-16LE.
+    ///
+    /// Zero-bytes on their own are left to be detected as binary, rather than as the start of a
+    /// UTF-32BE BOM.
+    pub(crate) fn from_truncated_bom(bytes: &[u8]) -> Option<Encoding> {
+        if bytes.iter().all(|b| *b == 0) || BOMS.iter().any(|(bom, _)| *bom == bytes) {
+            return None;
+        }
+
+        BOMS.iter()
+            .filter(|(bom, _)| bom.starts_with(bytes))
+            .min_by_key(|(bom, _)| bom.len())
+            .map(|(_, encoding)| *encoding)
+    }
+
+    /// Returns the BOM that is written before content in this encoding, if there is one
+    pub(crate) fn bom(&self) -> Option<&'static [u8]> {
+        BOMS.iter()
+            .find(|(_, encoding)| encoding == self)
+            .map(|(bom, _)| *bom)
+    }
+
+    /// Suggest the encoding that writes the text in the fewest bytes: [Encoding::Utf16Le] if that's
+    /// smaller than [Encoding::Utf8], e.g. for text that is mostly CJK characters, and
+    /// [Encoding::Utf8] otherwise, including when they are the same size.
+    pub fn recommended_for(s: &str) -> Encoding {
+        if Encoding::Utf16Le.encoded_len(s) < Encoding::Utf8.encoded_len(s) {
+            Encoding::Utf16Le
+        } else {
+            Encoding::Utf8
+        }
+    }
+
+    /// Returns the number of bytes the string takes up when written in this encoding, including
+    /// the BOM. Only [Encoding::Utf7] has to be encoded to be measured.
+    pub(crate) fn encoded_len(&self, s: &str) -> usize {
+        let bom_length = self.bom().map_or(0, <[u8]>::len);
+        let content_length: usize = match self {
+            // The length of a UTF-7 shifted sequence depends on how many characters are in it
+            Encoding::Utf7 => utf7::encode(s).len(),
+            encoding => s.chars().map(|c| encoding.char_len(c)).sum(),
+        };
+
+        bom_length + content_length
+    }
+
+    /// Returns the number of bytes the character takes up in this encoding.
+    /// Characters that can't be represented in a single-byte encoding are counted as one byte.
+    /// UTF-7 characters are measured as if they were encoded on their own, as the length of a
+    /// shifted sequence depends on the characters around it.
+    pub(crate) fn char_len(&self, c: char) -> usize {
+        match self {
+            Encoding::Utf8 | Encoding::Utf8Bom => c.len_utf8(),
+            Encoding::Utf16Be | Encoding::Utf16Le => c.len_utf16() * 2,
+            Encoding::Utf32Be | Encoding::Utf32Le => 4,
+            Encoding::Utf7 => utf7::encode(c.encode_utf8(&mut [0; 4])).len(),
+            Encoding::Windows1252 | Encoding::Cp037 => 1,
+            #[cfg(feature = "encoding_rs")]
+            Encoding::Other(name) => {
+                to_other(c.encode_utf8(&mut [0; 4]), name).map_or(1, |b| b.len())
+            }
+        }
+    }
 }
 
 impl From<Encoding> for String {
@@ -24,12 +318,54 @@ impl Display for Encoding {
             Encoding::Utf8Bom => write!(f, "UTF-8-BOM"),
             Encoding::Utf16Be => write!(f, "UTF-16-BE"),
             Encoding::Utf16Le => write!(f, "UTF-16-LE"),
+            Encoding::Utf32Be => write!(f, "UTF-32-BE"),
+            Encoding::Utf32Le => write!(f, "UTF-32-LE"),
+            Encoding::Utf7 => write!(f, "UTF-7"),
+            Encoding::Windows1252 => write!(f, "Windows-1252"),
+            Encoding::Cp037 => write!(f, "IBM037"),
+            #[cfg(feature = "encoding_rs")]
+            Encoding::Other(name) => write!(f, "{name}"),
         }
     }
 }
 
+/// Parses the names produced by the [Display] impl, e.g. "UTF-16-LE", along with common aliases.
+/// Names are matched case-insensitively and ignoring `-` and `_`, so "utf16le", "UTF16LE" and
+/// "utf_16_le" are all [Encoding::Utf16Le].
+///
+/// With the `encoding_rs` feature, any other WHATWG label is accepted as described for
+/// [Encoding::from_label].
+impl FromStr for Encoding {
+    type Err = ParseEncodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        let encoding = match normalized.as_str() {
+            "utf8" => Some(Encoding::Utf8),
+            "utf8bom" => Some(Encoding::Utf8Bom),
+            "utf16be" => Some(Encoding::Utf16Be),
+            "utf16le" => Some(Encoding::Utf16Le),
+            "utf32be" => Some(Encoding::Utf32Be),
+            "utf32le" => Some(Encoding::Utf32Le),
+            "utf7" => Some(Encoding::Utf7),
+            "windows1252" | "cp1252" => Some(Encoding::Windows1252),
+            "ibm037" | "cp037" | "ebcdiccpus" => Some(Encoding::Cp037),
+            _ => None,
+        };
+        #[cfg(feature = "encoding_rs")]
+        let encoding = encoding.or_else(|| Encoding::from_label(s));
+
+        encoding.ok_or_else(|| ParseEncodingError { name: s.into() })
+    }
+}
+
 /// Encodes a [String] into bytes using [Encoding::Utf8]
-pub fn to_utf8_bom(s: &String) -> Vec<u8> {
+pub fn to_utf8_bom(s: &str) -> Vec<u8> {
     [UTF8_BOM, s.as_bytes()].concat()
 }
 
@@ -59,11 +395,214 @@ pub fn to_utf16_le(s: &str) -> Vec<u8> {
     bytes
 }
 
+/// Encodes a [String] into bytes using [Encoding::Utf32Be]
+pub fn to_utf32_be(s: &str) -> Vec<u8> {
+    let mut bytes = UTF32BE_BOM.to_vec();
+    for c in s.chars() {
+        bytes.extend_from_slice((c as u32).to_be_bytes().as_slice())
+    }
+
+    bytes
+}
+
+/// Encodes a [String] into bytes using [Encoding::Utf32Le]
+pub fn to_utf32_le(s: &str) -> Vec<u8> {
+    let mut bytes = UTF32LE_BOM.to_vec();
+    for c in s.chars() {
+        bytes.extend_from_slice((c as u32).to_le_bytes().as_slice())
+    }
+
+    bytes
+}
+
+/// Encodes a [String] into bytes using [Encoding::Windows1252]
+/// UnmappableCharError will be returned for the first character that has no Windows-1252 byte
+pub fn to_windows_1252(s: &str) -> Result<Vec<u8>, UnmappableCharError> {
+    to_windows_1252_with(s, Unmappable::Error)
+}
+
+/// Encodes a [String] into bytes using [Encoding::Windows1252], handling characters that have no
+/// Windows-1252 byte as configured by `unmappable`
+pub fn to_windows_1252_with(
+    s: &str,
+    unmappable: Unmappable,
+) -> Result<Vec<u8>, UnmappableCharError> {
+    to_single_byte_with(s, Encoding::Windows1252, windows1252::from_char, unmappable)
+}
+
+/// Encodes a [String] into bytes using [Encoding::Cp037]
+/// UnmappableCharError will be returned for the first character that has no CP037 byte
+pub fn to_cp037(s: &str) -> Result<Vec<u8>, UnmappableCharError> {
+    to_cp037_with(s, Unmappable::Error)
+}
+
+/// Encodes a [String] into bytes using [Encoding::Cp037], handling characters that have no CP037
+/// byte as configured by `unmappable`
+pub fn to_cp037_with(s: &str, unmappable: Unmappable) -> Result<Vec<u8>, UnmappableCharError> {
+    to_single_byte_with(s, Encoding::Cp037, cp037::from_char, unmappable)
+}
+
+/// Encodes a [String] into bytes of a single-byte `encoding`, using `from_char` to map each [char]
+fn to_single_byte_with(
+    s: &str,
+    encoding: Encoding,
+    from_char: fn(char) -> Option<u8>,
+    unmappable: Unmappable,
+) -> Result<Vec<u8>, UnmappableCharError> {
+    s.chars()
+        .filter_map(|c| match (from_char(c), unmappable) {
+            (Some(byte), _) | (None, Unmappable::Replace(byte)) => Some(Ok(byte)),
+            (None, Unmappable::Skip) => None,
+            (None, Unmappable::Error) => Some(Err(UnmappableCharError {
+                character: c,
+                encoding,
+            })),
+        })
+        .collect()
+}
+
+/// Returns the `encoding_rs` encoding with the given name, which must have come from one
+#[cfg(feature = "encoding_rs")]
+pub(crate) fn other_encoding(name: &str) -> &'static encoding_rs::Encoding {
+    encoding_rs::Encoding::for_label(name.as_bytes()).expect("Names are valid labels")
+}
+
+/// Encodes a [String] into bytes using [Encoding::Other] with the given name
+/// UnmappableCharError will be returned for the first character that can't be represented in it
+#[cfg(feature = "encoding_rs")]
+fn to_other(s: &str, name: &'static str) -> Result<Vec<u8>, UnmappableCharError> {
+    let (bytes, _, had_unmappable) = other_encoding(name).encode(s);
+    if !had_unmappable {
+        return Ok(bytes.into_owned());
+    }
+
+    // encoding_rs replaces unmappable characters rather than reporting them, so find the first one
+    let character = s
+        .chars()
+        .find(|c| other_encoding(name).encode(c.encode_utf8(&mut [0; 4])).2)
+        .unwrap_or(char::REPLACEMENT_CHARACTER);
+    Err(UnmappableCharError {
+        character,
+        encoding: Encoding::Other(name),
+    })
+}
+
+/// The confidence that the bytes are UTF-8, as described for [Encoding::detect_with_confidence].
+/// A sequence cut off at the end of the bytes is counted as valid, as it may continue after them.
+fn utf8_confidence(bytes: &[u8]) -> f32 {
+    let non_ascii = bytes.iter().filter(|b| !b.is_ascii()).count();
+    if non_ascii == 0 {
+        return ASCII_UTF8_CONFIDENCE;
+    }
+
+    let mut invalid = 0;
+    let mut rest = bytes;
+    while let Err(e) = std::str::from_utf8(rest) {
+        let Some(error_len) = e.error_len() else {
+            break;
+        };
+        invalid += error_len;
+        rest = &rest[e.valid_up_to() + error_len..];
+    }
+
+    MULTI_BYTE_UTF8_CONFIDENCE * (non_ascii - invalid) as f32 / non_ascii as f32
+}
+
 #[cfg(test)]
 mod tests {
     use test_case::test_case;
 
-    use super::{to_utf16_be, to_utf16_le, to_utf8_bom};
+    use super::{
+        to_utf16_be, to_utf16_le, to_utf32_be, to_utf32_le, to_utf8_bom, to_windows_1252,
+        to_windows_1252_with, Encoding, ParseEncodingError, Unmappable, UnmappableCharError,
+    };
+
+    #[test_case(b"", Some(Encoding::Utf8); "no content")]
+    #[test_case(b"Hello!", Some(Encoding::Utf8); "no BOM")]
+    #[test_case(b"\xC1\x80", Some(Encoding::Utf8); "invalid UTF-8 is not validated")]
+    #[test_case(b"\xEF\xBB\xBFHello!", Some(Encoding::Utf8Bom); "UTF-8 BOM")]
+    #[test_case(b"\xFE\xFF\x00\x48", Some(Encoding::Utf16Be); "UTF-16BE BOM")]
+    #[test_case(b"\xFF\xFE\x48\x00", Some(Encoding::Utf16Le); "UTF-16LE BOM")]
+    #[test_case(b"\x00\x00\xFE\xFF", Some(Encoding::Utf32Be); "UTF-32BE BOM")]
+    #[test_case(b"\xFF\xFE\x00\x00", Some(Encoding::Utf32Le); "UTF-32LE BOM")]
+    #[test_case(b"\x12\x00\x34", None; "binary")]
+    #[test_case(b"H\x00e\x00l\x00l\x00o\x00!\x00", None; "BOM-less UTF-16LE is not detected")]
+    fn detect(bytes: &[u8], expected: Option<Encoding>) {
+        assert_eq!(Encoding::detect(bytes), expected);
+    }
+
+    #[test_case(b"\xEF\xBB\xBFHello!", Some(Encoding::Utf8Bom), 1.0; "UTF-8 BOM")]
+    #[test_case(b"\xFF\xFEH\x00i\x00", Some(Encoding::Utf16Le), 1.0; "UTF-16LE BOM")]
+    #[test_case(b"\x00\x00\xFE\xFF", Some(Encoding::Utf32Be), 1.0; "UTF-32BE BOM")]
+    #[test_case(b"\x12\x00\x34", None, 1.0; "binary")]
+    #[test_case(b"H\x00e\x00l\x00l\x00o\x00!\x00", None, 1.0; "BOM-less UTF-16LE")]
+    #[test_case(b"Hello!", Some(Encoding::Utf8), 0.9; "ASCII")]
+    #[test_case(b"", Some(Encoding::Utf8), 0.9; "no content")]
+    #[test_case("Hello! 你好! 🌍".as_bytes(), Some(Encoding::Utf8), 0.99; "multi-byte UTF-8")]
+    #[test_case(b"Hello! \xE4\xBD", Some(Encoding::Utf8), 0.99; "cut off multi-byte UTF-8")]
+    #[test_case(b"Caf\xE9 na\xEFve \xC3\xA9", Some(Encoding::Utf8), 0.495; "half invalid UTF-8")]
+    #[test_case(b"Caf\xE9 cr\xE8me", Some(Encoding::Utf8), 0.0; "Windows-1252")]
+    fn detect_with_confidence(bytes: &[u8], expected: Option<Encoding>, confidence: f32) {
+        let (encoding, subject) = Encoding::detect_with_confidence(bytes);
+
+        assert_eq!(encoding, expected);
+        assert!(
+            (subject - confidence).abs() < 0.001,
+            "{subject} != {confidence}"
+        );
+    }
+
+    /// A reader that fails if anything past its first `limit` bytes is read
+    struct LimitedReader<'a> {
+        bytes: &'a [u8],
+        limit: usize,
+    }
+
+    impl std::io::Read for LimitedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.limit == 0 && !buf.is_empty() {
+                return Err(std::io::Error::other("Read past the sniff window"));
+            }
+            let length = buf.len().min(self.limit).min(self.bytes.len());
+            buf[..length].copy_from_slice(&self.bytes[..length]);
+            self.bytes = &self.bytes[length..];
+            self.limit -= length;
+            Ok(length)
+        }
+    }
+
+    #[test_case(b"\xFF\xFEH\x00i\x00\x00\x00", 4, Some(Encoding::Utf16Le); "BOM")]
+    #[test_case(b"Hello!\0", 6, Some(Encoding::Utf8); "zero-byte past the window")]
+    #[test_case(b"\x12\x00\x34\x56", 3, None; "binary")]
+    #[test_case(b"Hi", 2, Some(Encoding::Utf8); "window at the end")]
+    fn detect_from_reader(bytes: &[u8], sniff_bytes: usize, expected: Option<Encoding>) {
+        let mut reader = LimitedReader {
+            bytes,
+            limit: sniff_bytes,
+        };
+        let subject = Encoding::detect_from_reader(&mut reader, sniff_bytes).expect("Should pass");
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case("", Encoding::Utf8; "no content")]
+    #[test_case("Hello, World!", Encoding::Utf8; "ASCII")]
+    #[test_case("Hello, 世界!", Encoding::Utf8; "mostly ASCII")]
+    #[test_case("你好，世界！你好", Encoding::Utf16Le; "CJK")]
+    #[test_case("你好", Encoding::Utf8; "tie")]
+    fn recommended_for(s: &str, expected: Encoding) {
+        assert_eq!(Encoding::recommended_for(s), expected);
+    }
+
+    #[test_case(b"", true; "no content")]
+    #[test_case(b"Hello! \xE4\xBD\xA0\xE5\xA5\xBD", true; "no BOM")]
+    #[test_case(b"\xEF\xBB\xBFHello!", true; "UTF-8 BOM")]
+    #[test_case(b"\xC1\x80", false; "overlong encoding")]
+    #[test_case(b"\xFF\xFEH\x00", false; "UTF-16LE BOM")]
+    #[test_case(b"\xEF\xBB", false; "truncated UTF-8 BOM")]
+    fn is_valid_utf8(bytes: &[u8], expected: bool) {
+        assert_eq!(Encoding::is_valid_utf8(bytes), expected);
+    }
 
     #[test_case("", b"\xEF\xBB\xBF"; "no chars")] // BOM is always added
     #[test_case("Hello!", b"\xEF\xBB\xBF\x48\x65\x6C\x6C\x6F\x21"; "ascii chars (8-bit chars)")]
@@ -71,7 +610,7 @@ mod tests {
     #[test_case("你好", b"\xEF\xBB\xBF\xE4\xBD\xA0\xE5\xA5\xBD"; "mandarin chars (24-bit chars)")]
     #[test_case("🌍🚀", b"\xEF\xBB\xBF\xF0\x9F\x8C\x8D\xF0\x9F\x9A\x80"; "Supplementary Multilingual Plane chars (32-bit chars)")]
     fn test_to_utf8_bom(input: &str, expected_bytes: &[u8]) {
-        let bytes = to_utf8_bom(&input.into());
+        let bytes = to_utf8_bom(input);
         assert_eq!(bytes, expected_bytes);
     }
 
@@ -92,4 +631,193 @@ mod tests {
         let bytes = to_utf16_le(input);
         assert_eq!(bytes, expected_bytes);
     }
+
+    #[test_case("", b"\x00\x00\xFE\xFF"; "no chars")]
+    #[test_case("Hi!", b"\x00\x00\xFE\xFF\x00\x00\x00\x48\x00\x00\x00\x69\x00\x00\x00\x21"; "ascii chars")]
+    #[test_case("你🌍", b"\x00\x00\xFE\xFF\x00\x00\x4F\x60\x00\x01\xF3\x0D"; "mixed-length chars with BE BOM")]
+    fn test_to_utf32_be(input: &str, expected_bytes: &[u8]) {
+        let bytes = to_utf32_be(input);
+        assert_eq!(bytes, expected_bytes);
+    }
+
+    #[test_case("", b"\xFF\xFE\x00\x00"; "no chars")]
+    #[test_case("Hi!", b"\xFF\xFE\x00\x00\x48\x00\x00\x00\x69\x00\x00\x00\x21\x00\x00\x00"; "ascii chars")]
+    #[test_case("你🌍", b"\xFF\xFE\x00\x00\x60\x4F\x00\x00\x0D\xF3\x01\x00"; "mixed-length chars with LE BOM")]
+    fn test_to_utf32_le(input: &str, expected_bytes: &[u8]) {
+        let bytes = to_utf32_le(input);
+        assert_eq!(bytes, expected_bytes);
+    }
+
+    #[test_case("", b""; "no chars")] // There is no BOM
+    #[test_case("Hello!", b"\x48\x65\x6C\x6C\x6F\x21"; "ascii chars")]
+    #[test_case("éüñç", b"\xE9\xFC\xF1\xE7"; "latin-1 chars")]
+    #[test_case("€5 – “ok”", b"\x80\x35\x20\x96\x20\x93\x6F\x6B\x94"; "windows-1252 specific chars")]
+    fn test_to_windows_1252(input: &str, expected_bytes: &[u8]) {
+        let bytes = to_windows_1252(input).expect("Should pass");
+        assert_eq!(bytes, expected_bytes);
+    }
+
+    #[test]
+    fn test_to_windows_1252_unmappable() {
+        let subject = to_windows_1252("Hello! 🌍");
+        let expected = UnmappableCharError {
+            character: '🌍',
+            encoding: Encoding::Windows1252,
+        };
+
+        assert_eq!(subject, Err(expected));
+    }
+
+    #[test_case(Unmappable::Replace(b'?'), b"\x80 ? ok"; "replace")]
+    #[test_case(Unmappable::Skip, b"\x80  ok"; "skip")]
+    fn test_to_windows_1252_with(unmappable: Unmappable, expected_bytes: &[u8]) {
+        let bytes = to_windows_1252_with("€ 🌍 ok", unmappable).expect("Should pass");
+        assert_eq!(bytes, expected_bytes);
+    }
+
+    #[test_case(Encoding::Utf8, "", b""; "UTF-8 no chars")]
+    #[test_case(Encoding::Utf8, "Hello! 😊", b"Hello! \xF0\x9F\x98\x8A"; "UTF-8 has no BOM")]
+    #[test_case(Encoding::Utf8Bom, "", b"\xEF\xBB\xBF"; "UTF-8-BOM no chars")]
+    #[test_case(Encoding::Utf8Bom, "你好", b"\xEF\xBB\xBF\xE4\xBD\xA0\xE5\xA5\xBD"; "UTF-8-BOM mandarin chars")]
+    #[test_case(Encoding::Utf16Be, "", b"\xFE\xFF"; "UTF-16BE no chars")]
+    #[test_case(Encoding::Utf16Be, "Hello! 😊", b"\xFE\xFF\x00\x48\x00\x65\x00\x6C\x00\x6C\x00\x6F\x00\x21\x00\x20\xD8\x3D\xDE\x0A"; "UTF-16BE mixed-length chars")]
+    #[test_case(Encoding::Utf16Le, "", b"\xFF\xFE"; "UTF-16LE no chars")]
+    #[test_case(Encoding::Utf16Le, "Hello! 😊", b"\xFF\xFE\x48\x00\x65\x00\x6C\x00\x6C\x00\x6F\x00\x21\x00\x20\x00\x3D\xD8\x0A\xDE"; "UTF-16LE mixed-length chars")]
+    #[test_case(Encoding::Utf32Be, "你🌍", b"\x00\x00\xFE\xFF\x00\x00\x4F\x60\x00\x01\xF3\x0D"; "UTF-32BE mixed-length chars")]
+    #[test_case(Encoding::Utf32Le, "你🌍", b"\xFF\xFE\x00\x00\x60\x4F\x00\x00\x0D\xF3\x01\x00"; "UTF-32LE mixed-length chars")]
+    #[test_case(Encoding::Utf7, "1 + 1 ≢ 3", b"1 +- 1 +ImI 3"; "UTF-7 mixed chars")]
+    #[test_case(Encoding::Windows1252, "€5 – “ok”", b"\x80\x35\x20\x96\x20\x93\x6F\x6B\x94"; "Windows-1252 specific chars")]
+    #[test_case(Encoding::Cp037, "Hello", b"\xC8\x85\x93\x93\x96"; "CP037 ascii chars")]
+    fn encode(encoding: Encoding, input: &str, expected_bytes: &[u8]) {
+        let bytes = encoding.encode(input).expect("Should pass");
+        assert_eq!(bytes, expected_bytes);
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn encode_other_unmappable() {
+        let subject = Encoding::Other("Shift_JIS").encode("こんにちは 🌍");
+        let expected = UnmappableCharError {
+            character: '🌍',
+            encoding: Encoding::Other("Shift_JIS"),
+        };
+
+        assert_eq!(subject, Err(expected));
+    }
+
+    #[test_case(Encoding::Windows1252)]
+    #[test_case(Encoding::Cp037)]
+    fn encode_unmappable(encoding: Encoding) {
+        let subject = encoding.encode("Hello! 🌍");
+        let expected = UnmappableCharError {
+            character: '🌍',
+            encoding,
+        };
+
+        assert_eq!(subject, Err(expected));
+    }
+
+    #[test_case(Encoding::Utf8, None, 1, false)]
+    #[test_case(Encoding::Utf8Bom, None, 1, true)]
+    #[test_case(Encoding::Utf16Be, Some(true), 2, true)]
+    #[test_case(Encoding::Utf16Le, Some(false), 2, true)]
+    #[test_case(Encoding::Utf32Be, Some(true), 4, true)]
+    #[test_case(Encoding::Utf32Le, Some(false), 4, true)]
+    #[test_case(Encoding::Utf7, None, 1, false)]
+    #[test_case(Encoding::Windows1252, None, 1, false)]
+    #[test_case(Encoding::Cp037, None, 1, false)]
+    fn metadata(
+        encoding: Encoding,
+        is_big_endian: Option<bool>,
+        code_unit_bytes: usize,
+        has_bom: bool,
+    ) {
+        assert_eq!(encoding.is_big_endian(), is_big_endian);
+        assert_eq!(encoding.code_unit_bytes(), code_unit_bytes);
+        assert_eq!(encoding.has_bom(), has_bom);
+    }
+
+    #[test_case(Encoding::Utf8, "UTF-8")]
+    #[test_case(Encoding::Utf8Bom, "UTF-8")]
+    #[test_case(Encoding::Utf16Be, "UTF-16BE")]
+    #[test_case(Encoding::Utf16Le, "UTF-16LE")]
+    #[test_case(Encoding::Utf32Be, "UTF-32BE")]
+    #[test_case(Encoding::Utf32Le, "UTF-32LE")]
+    #[test_case(Encoding::Utf7, "UTF-7")]
+    #[test_case(Encoding::Windows1252, "windows-1252")]
+    #[test_case(Encoding::Cp037, "IBM037")]
+    fn iana_name(encoding: Encoding, expected: &str) {
+        assert_eq!(encoding.iana_name(), expected);
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn iana_name_other() {
+        assert_eq!(Encoding::Other("Shift_JIS").iana_name(), "Shift_JIS");
+    }
+
+    #[test]
+    fn all() {
+        for (index, encoding) in Encoding::all().iter().enumerate() {
+            // A new variant makes this match non-exhaustive, as a reminder to add it to the list
+            let expected = match encoding {
+                Encoding::Utf8 => 0,
+                Encoding::Utf8Bom => 1,
+                Encoding::Utf16Be => 2,
+                Encoding::Utf16Le => 3,
+                Encoding::Utf32Be => 4,
+                Encoding::Utf32Le => 5,
+                Encoding::Utf7 => 6,
+                Encoding::Windows1252 => 7,
+                Encoding::Cp037 => 8,
+                #[cfg(feature = "encoding_rs")]
+                Encoding::Other(_) => panic!("encoding_rs encodings aren't listed"),
+            };
+            assert_eq!(index, expected);
+        }
+
+        assert_eq!(Encoding::all().len(), 9);
+    }
+
+    #[test_case(Encoding::Utf8)]
+    #[test_case(Encoding::Utf8Bom)]
+    #[test_case(Encoding::Utf16Be)]
+    #[test_case(Encoding::Utf16Le)]
+    #[test_case(Encoding::Utf32Be)]
+    #[test_case(Encoding::Utf32Le)]
+    #[test_case(Encoding::Utf7)]
+    #[test_case(Encoding::Windows1252)]
+    #[test_case(Encoding::Cp037)]
+    fn from_str_round_trip(encoding: Encoding) {
+        assert_eq!(encoding.to_string().parse(), Ok(encoding));
+    }
+
+    #[test_case("utf8", Encoding::Utf8)]
+    #[test_case("utf-8-bom", Encoding::Utf8Bom)]
+    #[test_case("utf-16le", Encoding::Utf16Le)]
+    #[test_case("UTF16LE", Encoding::Utf16Le)]
+    #[test_case("utf_16_be", Encoding::Utf16Be)]
+    #[test_case("Utf32Le", Encoding::Utf32Le)]
+    #[test_case("cp1252", Encoding::Windows1252)]
+    #[test_case("cp037", Encoding::Cp037)]
+    #[test_case("EBCDIC-CP-US", Encoding::Cp037)]
+    fn from_str_alias(name: &str, expected: Encoding) {
+        assert_eq!(name.parse(), Ok(expected));
+    }
+
+    #[test]
+    fn from_str_unknown() {
+        let subject = "UTF-9".parse::<Encoding>();
+
+        assert_eq!(
+            subject,
+            Err(ParseEncodingError {
+                name: "UTF-9".into()
+            })
+        );
+        assert_eq!(
+            subject.unwrap_err().to_string(),
+            "Unknown encoding name \"UTF-9\""
+        );
+    }
 }
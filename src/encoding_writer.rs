@@ -0,0 +1,128 @@
+use std::io::Write;
+
+use crate::encoding::Encoding;
+
+/// Encodes text into a [Write] as it is produced, without holding all of it in memory at once.
+/// This is the streaming counterpart to [crate::FileContent::write].
+///
+/// The BOM of the [Encoding], if it has one, is written before the first chunk, and never again.
+/// Each chunk is encoded on its own, so the bytes are the same as encoding all of the text at once,
+/// except for [Encoding::Utf7], where every chunk that ends in a shifted sequence ends it
+/// explicitly. The content still decodes to the same text.
+pub struct EncodingWriter<W: Write> {
+    writer: W,
+    encoding: Encoding,
+    bom_written: bool,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    /// Create an [EncodingWriter] that writes text to the given output in the given [Encoding]
+    pub fn new(writer: W, encoding: Encoding) -> Self {
+        EncodingWriter {
+            writer,
+            encoding,
+            bom_written: false,
+        }
+    }
+
+    /// The encoding that text is written in
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Encode the text and write it, writing the BOM first if this is the first write.
+    ///
+    /// An error with [std::io::ErrorKind::InvalidData] is returned if the text contains a character
+    /// that can't be represented in the [Encoding], in which case nothing is written for the chunk.
+    pub fn write_str(&mut self, s: &str) -> Result<(), std::io::Error> {
+        let bytes = self
+            .encoding
+            .encode(s)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        let bom_length = self.encoding.bom().map_or(0, <[u8]>::len);
+
+        self.write_bom()?;
+        self.writer.write_all(&bytes[bom_length..])
+    }
+
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.writer.flush()
+    }
+
+    /// Write the BOM if nothing has been written yet, so empty content still has one as it would
+    /// with [crate::FileContent::write], then flush and return the underlying writer.
+    pub fn finish(mut self) -> Result<W, std::io::Error> {
+        self.write_bom()?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_bom(&mut self) -> Result<(), std::io::Error> {
+        if !self.bom_written {
+            self.writer
+                .write_all(self.encoding.bom().unwrap_or_default())?;
+            self.bom_written = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::encoding::{to_utf16_be, to_utf16_le, to_utf32_le, to_utf8_bom, Encoding};
+    use crate::encoding_writer::EncodingWriter;
+
+    const CHUNKS: [&str; 4] = ["Hello! ", "你好", "", "! 🌍"];
+
+    #[test_case(Encoding::Utf16Le, to_utf16_le; "UTF-16LE")]
+    #[test_case(Encoding::Utf16Be, to_utf16_be; "UTF-16BE")]
+    #[test_case(Encoding::Utf32Le, to_utf32_le; "UTF-32LE")]
+    #[test_case(Encoding::Utf8Bom, to_utf8_bom; "UTF-8 with BOM")]
+    #[test_case(Encoding::Utf8, |s| s.as_bytes().to_vec(); "UTF-8")]
+    fn write_chunks(encoding: Encoding, encode: fn(&str) -> Vec<u8>) {
+        let mut subject = EncodingWriter::new(vec![], encoding);
+        for chunk in CHUNKS {
+            subject.write_str(chunk).expect("Should pass");
+        }
+
+        assert_eq!(
+            subject.finish().expect("Should pass"),
+            encode(&CHUNKS.concat())
+        );
+    }
+
+    #[test]
+    fn finish_without_writing() {
+        let subject = EncodingWriter::new(vec![], Encoding::Utf16Le);
+
+        assert_eq!(subject.finish().expect("Should pass"), to_utf16_le(""));
+    }
+
+    #[test]
+    fn write_unmappable() {
+        let mut subject = EncodingWriter::new(vec![], Encoding::Windows1252);
+        subject.write_str("Café ").expect("Should pass");
+        let error = subject.write_str("🌍").expect_err("Should fail");
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(subject.finish().expect("Should pass"), b"Caf\xE9 ");
+    }
+
+    #[test]
+    fn write_utf7_chunks() {
+        let mut subject = EncodingWriter::new(vec![], Encoding::Utf7);
+        subject.write_str("Hi Mom \u{263A}").expect("Should pass");
+        subject.write_str("\u{263A}!").expect("Should pass");
+        let bytes = subject.finish().expect("Should pass");
+
+        assert_eq!(bytes, b"Hi Mom +Jjo-+Jjo!");
+        assert_eq!(
+            crate::utf7::decode(&bytes),
+            Ok("Hi Mom \u{263A}\u{263A}!".into())
+        );
+    }
+}
@@ -1,43 +1,321 @@
 use std::{
+    borrow::Cow,
     fmt::Display,
     fs,
     io::{Read, Write},
     path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 use crate::{
-    encoding::{to_utf16_be, to_utf16_le, to_utf8_bom, Encoding},
-    text_data::TextData,
+    binary::BinaryHeuristic,
+    encoding::{to_cp037_with, to_windows_1252_with, Encoding, Unmappable},
+    gitattributes::GitAttributes,
+    incremental::IncrementalDecoder,
+    line_ending::LineEnding,
+    text_data::{TextData, TextDataError, TextDataOptions},
 };
 
+/// The number of leading bytes of binary content shown by [File::summary]
+const SUMMARY_BINARY_BYTES: usize = 16;
+
+/// The number of bytes read at a time by [File::new_from_path_with_progress]
+const PROGRESS_CHUNK_BYTES: usize = 64 * 1024;
+
+/// The most bytes reserved up front by [File::new_from_path_with_progress], so that a wrong size in
+/// the metadata can't cause a huge allocation. Larger files grow the buffer as they are read.
+const PROGRESS_MAX_RESERVED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// The number of bytes on each line of [FileContent::hex_dump]
+const HEX_DUMP_LINE_BYTES: usize = 16;
+
+/// The magic numbers that binary content is recognised by in [FileContent::sniff_mime], along with
+/// the MIME type of the content that starts with them
+const MAGIC_NUMBERS: [(&[u8], &str); 8] = [
+    (b"\x89PNG\r\n\x1A\n", "image/png"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"PK\x05\x06", "application/zip"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"\xFF\xD8\xFF", "image/jpeg"),
+    (b"\x1F\x8B", "application/gzip"),
+];
+
 /// An enum that represents the possible contents of a file
-/// 
+///
 /// - `Encoded`: The content is a string that can be decoded as one of the
-/// supported encodings from [Encoding] (held in a [TextData])
+///   supported encodings from [Encoding] (held in a [TextData])
 /// - `Binary`: The content is a sequence of bytes that cannot be decoded as a string
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FileContent {
-    Encoded { content: TextData },
-    Binary { content: Vec<u8> },
+    Encoded {
+        content: TextData,
+    },
+    Binary {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::base64_bytes"))]
+        content: Vec<u8>,
+    },
 }
 
 impl FileContent {
+    /// Detect the encoding of the bytes, falling back to [FileContent::Binary] if they can't be decoded
+    fn detect(bytes: Vec<u8>) -> Self {
+        match TextData::try_from_vec_with_options(bytes, TextDataOptions::default()) {
+            Ok(content) => FileContent::Encoded { content },
+            Err((_, bytes)) => FileContent::Binary { content: bytes },
+        }
+    }
+
+    /// Detect the encoding of the bytes in the same way as [FileContent::detect], only copying them
+    /// if they are binary
+    fn detect_slice(bytes: &[u8]) -> Self {
+        match TextData::try_from(bytes) {
+            Ok(content) => FileContent::Encoded { content },
+            Err(_) => FileContent::Binary {
+                content: bytes.to_vec(),
+            },
+        }
+    }
+
     pub fn write<T: Write>(&self, writer: &mut T) -> Result<(), std::io::Error> {
+        self.write_with_options(writer, WriteOptions::default())
+    }
+
+    /// Write the content using its current encoding, as [FileContent::write] does, returning the
+    /// number of bytes written, including the BOM.
+    pub fn write_counted<T: Write>(&self, writer: &mut T) -> Result<usize, std::io::Error> {
+        let mut counter = CountingWriter { writer, count: 0 };
+        self.write(&mut counter)?;
+        Ok(counter.count)
+    }
+
+    /// The bytes that [FileContent::write] writes, e.g. to hash the content or pass it on without
+    /// a [Write] to write to. Binary content is copied as-is.
+    ///
+    /// This fails in the same way as [FileContent::write], if the content has characters that
+    /// can't be represented in its [Encoding].
+    pub fn to_bytes(&self) -> Result<Vec<u8>, std::io::Error> {
+        let mut bytes = vec![];
+        self.write(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Write the content using its current encoding, but with or without a BOM regardless of
+    /// whether the [Encoding] has one. E.g. [Encoding::Utf8] content is written with the UTF-8 BOM
+    /// if `include_bom` is true, and [Encoding::Utf16Le] content is written without a BOM if false.
+    ///
+    /// The flag is ignored for [Encoding::Utf7] and [Encoding::Windows1252], which have no BOM,
+    /// and for binary content, which is always written as-is.
+    pub fn write_with_bom<T: Write>(
+        &self,
+        writer: &mut T,
+        include_bom: bool,
+    ) -> Result<(), std::io::Error> {
+        let options = WriteOptions {
+            bom: Some(include_bom),
+            ..Default::default()
+        };
+        self.write_with_options(writer, options)
+    }
+
+    /// Whether there is no decoded content, or no bytes for binary content. Encoded content that was
+    /// only a BOM decodes to nothing, so it is empty, as described for [TextData::is_empty].
+    pub fn is_empty(&self) -> bool {
         match self {
-            FileContent::Encoded { content } => match content.encoding {
-                Encoding::Utf8 => writer.write_all(content.data.as_bytes()),
-                Encoding::Utf8Bom => writer.write_all(&to_utf8_bom(&content.data)),
-                Encoding::Utf16Be => writer.write_all(&to_utf16_be(&content.data)),
-                Encoding::Utf16Le => writer.write_all(&to_utf16_le(&content.data)),
-            },
+            FileContent::Encoded { content } => content.is_empty(),
+            FileContent::Binary { content } => content.is_empty(),
+        }
+    }
+
+    /// A hash of the bytes that [FileContent::write] produces, so content that is written
+    /// identically hashes identically, e.g. the same text as [Encoding::Utf8] and
+    /// [Encoding::Utf8Bom] hashes differently, as the bytes on disk differ.
+    ///
+    /// This uses 64-bit FNV-1a, so the hash is stable across runs and versions of this crate, but
+    /// it isn't suitable for security purposes. An error is returned if the content can't be
+    /// written, in the same way as [FileContent::write].
+    pub fn content_hash(&self) -> Result<u64, std::io::Error> {
+        let mut hasher = ContentHasher(FNV_OFFSET_BASIS);
+        self.write(&mut hasher)?;
+        Ok(hasher.0)
+    }
+
+    /// Format the first `max_bytes` bytes that [FileContent::write] produces as an `xxd`-style hex
+    /// dump: 16 bytes per line, each line starting with its offset and ending with the printable
+    /// ASCII characters of its bytes, e.g. to see why content was classed as binary.
+    ///
+    /// Characters in encoded content that can't be represented in its encoding are dumped as `?`.
+    /// Content that still can't be written, like [Encoding::Other] with such characters, dumps
+    /// nothing.
+    pub fn hex_dump(&self, max_bytes: usize) -> String {
+        let options = WriteOptions {
+            unmappable: Unmappable::Replace(b'?'),
+            ..Default::default()
+        };
+        let mut bytes = vec![];
+        if self.write_with_options(&mut bytes, options).is_err() {
+            bytes.clear();
+        }
+
+        bytes[..bytes.len().min(max_bytes)]
+            .chunks(HEX_DUMP_LINE_BYTES)
+            .enumerate()
+            .map(|(line, chunk)| {
+                let hex: Vec<String> = chunk
+                    .chunks(2)
+                    .map(|group| group.iter().map(|b| format!("{b:02x}")).collect())
+                    .collect();
+                let ascii: String = chunk
+                    .iter()
+                    .map(|b| match b {
+                        0x20..=0x7E => *b as char,
+                        _ => '.',
+                    })
+                    .collect();
+                format!(
+                    "{:08x}: {:<39}  {ascii}\n",
+                    line * HEX_DUMP_LINE_BYTES,
+                    hex.join(" ")
+                )
+            })
+            .collect()
+    }
+
+    /// A hint of the MIME type of the content. Binary content is recognised by the magic number it
+    /// starts with, for PNG, PDF, ZIP, GIF, JPEG and gzip files, and is `None` for anything else.
+    ///
+    /// Encoded content is "text/plain", with the [Encoding::iana_name] of its encoding as the
+    /// charset. Encodings supported through `encoding_rs` have no charset.
+    pub fn sniff_mime(&self) -> Option<&'static str> {
+        match self {
+            FileContent::Encoded { content } => Some(match content.encoding {
+                Encoding::Utf8 | Encoding::Utf8Bom => "text/plain; charset=UTF-8",
+                Encoding::Utf16Be => "text/plain; charset=UTF-16BE",
+                Encoding::Utf16Le => "text/plain; charset=UTF-16LE",
+                Encoding::Utf32Be => "text/plain; charset=UTF-32BE",
+                Encoding::Utf32Le => "text/plain; charset=UTF-32LE",
+                Encoding::Utf7 => "text/plain; charset=UTF-7",
+                Encoding::Windows1252 => "text/plain; charset=windows-1252",
+                Encoding::Cp037 => "text/plain; charset=IBM037",
+                #[cfg(feature = "encoding_rs")]
+                Encoding::Other(_) => "text/plain",
+            }),
+            FileContent::Binary { content } => MAGIC_NUMBERS
+                .iter()
+                .find(|(magic, _)| content.starts_with(magic))
+                .map(|(_, mime)| *mime),
+        }
+    }
+
+    /// Write the content using its current encoding, as configured by the [WriteOptions].
+    /// Binary content is always written as-is.
+    pub fn write_with_options<T: Write>(
+        &self,
+        writer: &mut T,
+        options: WriteOptions,
+    ) -> Result<(), std::io::Error> {
+        match self {
+            FileContent::Encoded { content } => {
+                let bytes = encode(content, options)?;
+                let Some(include_bom) = options.bom else {
+                    return writer.write_all(&bytes);
+                };
+
+                let current_bom = content.encoding.bom().unwrap_or_default();
+                let bom = match content.encoding {
+                    _ if !include_bom => None,
+                    Encoding::Utf8 => Encoding::Utf8Bom.bom(),
+                    encoding => encoding.bom(),
+                };
+
+                writer.write_all(bom.unwrap_or_default())?;
+                writer.write_all(&bytes[current_bom.len()..])
+            }
             FileContent::Binary { content } => writer.write_all(content),
         }
     }
 }
 
+/// A [Write] that passes the bytes written to it on to another, counting them
+struct CountingWriter<'a, W: Write> {
+    writer: &'a mut W,
+    count: usize,
+}
+
+impl<W: Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// A [Write] that computes the 64-bit FNV-1a hash of the bytes written to it
+struct ContentHasher(u64);
+
+impl Write for ContentHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for byte in buf {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(FNV_PRIME);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Options that control how a [FileContent] is written.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct WriteOptions {
+    /// Whether to write a BOM before the content, regardless of whether its [Encoding] has one,
+    /// as described for [FileContent::write_with_bom]. `None` (the default) writes a BOM only if
+    /// the [Encoding] has one.
+    pub bom: Option<bool>,
+    /// What to do with characters that can't be represented in a single-byte encoding, like
+    /// [Encoding::Windows1252] or [Encoding::Cp037]. Defaults to [Unmappable::Error], so no content is lost silently.
+    pub unmappable: Unmappable,
+    /// The style of line breaks to write the content with, e.g. [LineEnding::CrLf] for Windows,
+    /// whatever the line breaks in it are. The line breaks are rewritten as the content is
+    /// encoded, so the content itself isn't changed. `None` (the default) writes the line breaks
+    /// as they are, as do [LineEnding::Mixed] and [LineEnding::None], which aren't styles that
+    /// can be written.
+    pub line_ending: Option<LineEnding>,
+}
+
+/// Encodes the content into bytes using its [Encoding], including the BOM if it has one
+fn encode(content: &TextData, options: WriteOptions) -> Result<Cow<'_, [u8]>, std::io::Error> {
+    let data = match options.line_ending {
+        Some(line_ending) => line_ending.convert(&content.data),
+        None => Cow::Borrowed(content.data.as_str()),
+    };
+    let bytes = match (content.encoding, data) {
+        (Encoding::Utf8, Cow::Borrowed(data)) => return Ok(Cow::Borrowed(data.as_bytes())),
+        (Encoding::Utf8, Cow::Owned(data)) => return Ok(Cow::Owned(data.into_bytes())),
+        (Encoding::Windows1252, data) => to_windows_1252_with(&data, options.unmappable),
+        (Encoding::Cp037, data) => to_cp037_with(&data, options.unmappable),
+        (encoding, data) => encoding.encode(&data),
+    };
+
+    bytes
+        .map(Cow::Owned)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
 /// A file representation that can be used to pair a file path with its content.
 /// [File] provides convenience methods for working with files on disk, or in memory.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct File {
     pub path: PathBuf,
     pub content: FileContent,
@@ -46,11 +324,102 @@ pub struct File {
 /// Represents the possible errors that can occur when working with [File] structs.
 #[derive(Debug, thiserror::Error)]
 pub enum FileError {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+    #[error("{}{source}", path_prefix(path))]
+    Io {
+        #[source]
+        source: std::io::Error,
+        /// The path of the file or directory the error happened for, if there is one
+        path: Option<PathBuf>,
+    },
 
     #[error(transparent)]
     TextData(#[from] crate::text_data::TextDataError),
+
+    /// Content can't be appended to a file with a different [Encoding], as described for
+    /// [File::append_to_path]
+    #[error("{}: Can't append {expected} content to a file encoded as {found}", path.display())]
+    EncodingMismatch {
+        path: PathBuf,
+        expected: Encoding,
+        found: Encoding,
+    },
+
+    /// The path is a directory, so it can't be read as a file
+    #[error("{}: Is a directory, not a file", .0.display())]
+    NotAFile(PathBuf),
+
+    /// The path is empty, so there is no file to read
+    #[error("Can't read a file from an empty path")]
+    EmptyPath,
+
+    /// The file is larger than the limit given to [File::new_from_path_limited]. If the file was
+    /// found to be too large while reading it, `size` is the number of bytes read before stopping,
+    /// so it may be less than the size of the file.
+    #[error("The file is {size} bytes, which is over the limit of {limit} bytes")]
+    TooLarge { size: u64, limit: u64 },
+}
+
+impl From<std::io::Error> for FileError {
+    fn from(source: std::io::Error) -> Self {
+        FileError::Io { source, path: None }
+    }
+}
+
+fn path_prefix(path: &Option<PathBuf>) -> String {
+    path.as_ref()
+        .map(|path| format!("{}: ", path.display()))
+        .unwrap_or_default()
+}
+
+/// Checks that the path could be a file before reading it, as reading an empty path or a directory
+/// fails with an OS error that doesn't say what the problem is
+fn check_file_path(path: &Path) -> Result<(), FileError> {
+    if path.as_os_str().is_empty() {
+        Err(FileError::EmptyPath)
+    } else if path.is_dir() {
+        Err(FileError::NotAFile(path.to_path_buf()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns a function that converts an [std::io::Error] into a [FileError] for the given path
+pub(crate) fn io_error_at(path: &Path) -> impl Fn(std::io::Error) -> FileError + '_ {
+    move |source| FileError::Io {
+        source,
+        path: Some(path.to_path_buf()),
+    }
+}
+
+/// The paths of every regular file in the directory, and its subdirectories if `recursive` is true,
+/// as described for [File::read_dir]. Directories that can't be read are yielded as an `Err` item.
+fn walk_dir(dir: &Path, recursive: bool) -> impl Iterator<Item = Result<PathBuf, FileError>> {
+    let mut directories = vec![dir.to_path_buf()];
+    let mut entries: Option<fs::ReadDir> = None;
+
+    std::iter::from_fn(move || loop {
+        let entry = match entries.as_mut().and_then(Iterator::next) {
+            Some(entry) => entry,
+            None => {
+                let directory = directories.pop()?;
+                match fs::read_dir(&directory) {
+                    Ok(read_dir) => {
+                        entries = Some(read_dir);
+                        continue;
+                    }
+                    Err(e) => return Some(Err(io_error_at(&directory)(e))),
+                }
+            }
+        };
+
+        // The file type of an entry doesn't follow symlinks
+        match entry.and_then(|entry| Ok((entry.path(), entry.file_type()?))) {
+            Ok((path, file_type)) if file_type.is_file() => return Some(Ok(path)),
+            Ok((path, file_type)) if file_type.is_dir() && recursive => directories.push(path),
+            Ok(_) => {}
+            Err(e) => return Some(Err(e.into())),
+        }
+    })
 }
 
 impl Display for File {
@@ -80,49 +449,673 @@ impl File {
         let mut bytes: Vec<u8> = vec![];
         input.read_to_end(&mut bytes)?;
         let path = path.into();
-        let content = TextData::try_from(bytes.as_slice());
-        let content = if let Ok(content) = content {
-            FileContent::Encoded { content }
-        } else {
-            FileContent::Binary { content: bytes }
-        };
+        let content = FileContent::detect(bytes);
 
         Ok(File { path, content })
     }
 
+    /// Create a [File] with the given path from content that is already in memory, detecting its
+    /// encoding in the same way as [File::new]. Nothing is read from disk, and the bytes are only
+    /// copied if the content is binary. Content that can't be decoded is binary, so no error is
+    /// currently returned, but the result matches [File::new].
+    pub fn from_bytes(path: impl Into<PathBuf>, bytes: &[u8]) -> Result<Self, FileError> {
+        Ok(File {
+            path: path.into(),
+            content: FileContent::detect_slice(bytes),
+        })
+    }
+
+    /// Create a [File] with the given path and text content, which will be written in the given
+    /// [Encoding]. Nothing is read from disk, and the content isn't checked for being binary.
+    pub fn from_string(path: impl Into<PathBuf>, content: String, encoding: Encoding) -> Self {
+        File {
+            path: path.into(),
+            content: FileContent::Encoded {
+                content: TextData::new(content, encoding),
+            },
+        }
+    }
+
+    /// Create a [File] with the given path and binary content, which will be written as-is.
+    /// Nothing is read from disk, and the content isn't checked for being text.
+    pub fn from_bytes_binary(path: impl Into<PathBuf>, content: Vec<u8>) -> Self {
+        File {
+            path: path.into(),
+            content: FileContent::Binary { content },
+        }
+    }
+
+    /// Create a [File] by reading the content at the given path.
+    /// I/O errors include the path, as described for [FileError::Io].
     pub fn new_from_path(path: impl Into<PathBuf>) -> Result<Self, FileError> {
         let path = path.into();
-        let reader = std::fs::File::open(&path)?;
-        Self::new(path, reader)
+        check_file_path(&path)?;
+        let bytes = fs::read(&path).map_err(io_error_at(&path))?;
+        let content = FileContent::detect(bytes);
+
+        Ok(File { path, content })
+    }
+
+    /// Create a [File] by reading the content at the given path, as with [File::new_from_path],
+    /// unless the [GitAttributes] force the path to be text or binary, in which case that is used
+    /// instead of detecting whether the content is binary. Content forced to text still has its
+    /// encoding detected, so a [FileError::TextData] is returned if it can't be decoded.
+    pub fn new_with_gitattributes(
+        path: impl Into<PathBuf>,
+        attributes: &GitAttributes,
+    ) -> Result<Self, FileError> {
+        let path = path.into();
+        let Some(is_text) = attributes.is_text(&path) else {
+            return File::new_from_path(path);
+        };
+        check_file_path(&path)?;
+        let bytes = fs::read(&path).map_err(io_error_at(&path))?;
+
+        let content = match is_text {
+            true => {
+                let options = TextDataOptions {
+                    binary_heuristic: BinaryHeuristic::Custom(|_| false),
+                    ..Default::default()
+                };
+                FileContent::Encoded {
+                    content: TextData::try_from_bytes_with_options(&bytes, options)?,
+                }
+            }
+            false => FileContent::Binary { content: bytes },
+        };
+
+        Ok(File { path, content })
+    }
+
+    /// Create a [File] by reading the content at the given path, as [File::new_from_path] does,
+    /// calling `on_progress` as it's read, e.g. to show a progress bar for a large file.
+    ///
+    /// The file is read in 64 KiB chunks, and `on_progress` is called with the number of bytes read
+    /// so far and the size of the file from its metadata, if it's known, before the first chunk and
+    /// after each one. The content is decoded once all of it has been read.
+    pub fn new_from_path_with_progress(
+        path: impl Into<PathBuf>,
+        mut on_progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<Self, FileError> {
+        let path = path.into();
+        check_file_path(&path)?;
+        let mut file = fs::File::open(&path).map_err(io_error_at(&path))?;
+        let total = file.metadata().ok().map(|metadata| metadata.len());
+
+        let reserved = total.unwrap_or_default().min(PROGRESS_MAX_RESERVED_BYTES);
+        let mut bytes = Vec::with_capacity(reserved as usize);
+        let mut chunk = vec![0; PROGRESS_CHUNK_BYTES];
+        on_progress(0, total);
+        loop {
+            match file.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => {
+                    bytes.extend_from_slice(&chunk[..read]);
+                    on_progress(bytes.len() as u64, total);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(io_error_at(&path)(e)),
+            }
+        }
+        let content = FileContent::detect(bytes);
+
+        Ok(File { path, content })
+    }
+
+    /// Create a [File] by reading the content at the given path, as [File::new_from_path] does, but
+    /// failing with [FileError::TooLarge] rather than reading more than `max_bytes` into memory.
+    ///
+    /// The size of the file is checked before reading it. As the size in the metadata can be wrong,
+    /// e.g. for files that are generated as they are read or that grow while being read, no more
+    /// than one byte past the limit is ever read either.
+    pub fn new_from_path_limited(
+        path: impl Into<PathBuf>,
+        max_bytes: u64,
+    ) -> Result<Self, FileError> {
+        let path = path.into();
+        check_file_path(&path)?;
+        let file = fs::File::open(&path).map_err(io_error_at(&path))?;
+        let size = file.metadata().map_err(io_error_at(&path))?.len();
+        if size > max_bytes {
+            return Err(FileError::TooLarge {
+                size,
+                limit: max_bytes,
+            });
+        }
+
+        let mut bytes = Vec::with_capacity(size as usize);
+        let read = file
+            .take(max_bytes.saturating_add(1))
+            .read_to_end(&mut bytes)
+            .map_err(io_error_at(&path))? as u64;
+        if read > max_bytes {
+            return Err(FileError::TooLarge {
+                size: read,
+                limit: max_bytes,
+            });
+        }
+        let content = FileContent::detect(bytes);
+
+        Ok(File { path, content })
+    }
+
+    /// Read every regular file in the directory, and its subdirectories if `recursive` is true.
+    /// Symlinks are skipped, and files are yielded in the order the platform lists them.
+    ///
+    /// A failure to read a file or directory is yielded as an `Err` item, and iteration carries on
+    /// with the remaining files.
+    pub fn read_dir(
+        dir: impl AsRef<Path>,
+        recursive: bool,
+    ) -> impl Iterator<Item = Result<File, FileError>> {
+        walk_dir(dir.as_ref(), recursive).map(|path| path.and_then(File::new_from_path))
     }
 
     /// Save the content of a file to disk at it's [PathBuf], using the current encoding for the content.
     pub fn save_to_path(&self) -> Result<(), std::io::Error> {
-        let mut writer = fs::File::create(&self.path)?;
-        self.content.write(&mut writer)
+        self.save_to_path_counted().map(|_| ())
+    }
+
+    /// Save the content of a file to disk at it's [PathBuf], as [File::save_to_path] does, returning
+    /// the number of bytes written, including the BOM.
+    pub fn save_to_path_counted(&self) -> Result<usize, std::io::Error> {
+        self.write_to_path_counted(&self.path)
+    }
+
+    /// Save the content of a file to disk at it's [PathBuf], in the same way as [File::save_to_path],
+    /// but without leaving a truncated file behind if writing fails part way through.
+    ///
+    /// The content is written to a temporary file next to the target, which is then renamed over it.
+    /// If the rename fails because the files are on different devices, the temporary file is copied
+    /// instead. The temporary file is removed if anything fails.
+    pub fn save_atomically(&self) -> Result<(), std::io::Error> {
+        let temp_path = temp_path_for(&self.path);
+        let result =
+            self.write_to_path(&temp_path)
+                .and_then(|_| match fs::rename(&temp_path, &self.path) {
+                    Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+                        fs::copy(&temp_path, &self.path).map(|_| ())
+                    }
+                    result => result,
+                });
+
+        if temp_path.exists() {
+            // The original error is more useful than any failure to clean up
+            let _ = fs::remove_file(&temp_path);
+        }
+
+        result
+    }
+
+    /// Write the content of a file to disk at the given path, using the current encoding for the content.
+    /// Unlike [File::save_to_path], the file's own [PathBuf] is left unchanged.
+    pub fn write_to_path(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        self.write_to_path_counted(path).map(|_| ())
+    }
+
+    /// Write the content of a file to disk at the given path, as [File::write_to_path] does,
+    /// returning the number of bytes written, including the BOM.
+    fn write_to_path_counted(&self, path: impl AsRef<Path>) -> Result<usize, std::io::Error> {
+        let mut writer = fs::File::create(path)?;
+        self.content.write_counted(&mut writer)
+    }
+
+    /// Check that writing the content gives back exactly the bytes of the file on disk at it's
+    /// [PathBuf], e.g. to catch content that would change if it were read and saved unchanged.
+    ///
+    /// Returns false if the file can't be read, or the content can't be written in its encoding.
+    pub fn round_trips(&self) -> bool {
+        match (fs::read(&self.path), self.content.to_bytes()) {
+            (Ok(bytes), Ok(written)) => bytes == written,
+            _ => false,
+        }
+    }
+
+    /// Compare the content of two [File]s, ignoring their paths and the encodings of their content.
+    ///
+    /// Unlike `==`, encoded content is compared using [TextData::content_eq], so the same text read
+    /// from files with different encodings is equal. Binary content is compared byte for byte, and
+    /// is never equal to encoded content.
+    pub fn content_eq(&self, other: &File) -> bool {
+        match (&self.content, &other.content) {
+            (FileContent::Encoded { content: a }, FileContent::Encoded { content: b }) => {
+                a.content_eq(b)
+            }
+            (FileContent::Binary { content: a }, FileContent::Binary { content: b }) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Describe the file in the same layout as its [Display], but without flooding the output for
+    /// large files. Encoded content is cut off after `max_content_chars` [char]s, with an ellipsis
+    /// to show it was. Binary content is shown as a hex dump of its first 16 bytes and its length.
+    pub fn summary(&self, max_content_chars: usize) -> String {
+        match &self.content {
+            FileContent::Encoded { content } => {
+                let mut summary = format!(
+                    "File: {}\nEncoding: {}\nContent:\n",
+                    self.path.display(),
+                    content.encoding
+                );
+                match content.data.char_indices().nth(max_content_chars) {
+                    Some((end, _)) => {
+                        summary.push_str(&content.data[..end]);
+                        summary.push('…');
+                    }
+                    None => summary.push_str(&content.data),
+                }
+                summary
+            }
+            FileContent::Binary { content } => {
+                let hex: Vec<String> = content
+                    .iter()
+                    .take(SUMMARY_BINARY_BYTES)
+                    .map(|b| format!("{b:02X}"))
+                    .collect();
+                let ellipsis = if content.len() > SUMMARY_BINARY_BYTES {
+                    " …"
+                } else {
+                    ""
+                };
+                format!(
+                    "File: {}\nEncoding: Binary\nContent:\n{}{} ({} bytes)",
+                    self.path.display(),
+                    hex.join(" "),
+                    ellipsis,
+                    content.len()
+                )
+            }
+        }
+    }
+
+    /// Change the encoding that will be used when the content is written.
+    /// The decoded content is independent of its encoding, so only the [TextData::encoding] changes.
+    ///
+    /// Binary content has no encoding to convert, so [TextDataError::Binary] is returned for it.
+    pub fn convert_to(&mut self, target: Encoding) -> Result<(), FileError> {
+        match &mut self.content {
+            FileContent::Encoded { content } => {
+                content.encoding = target;
+                Ok(())
+            }
+            FileContent::Binary { .. } => Err(TextDataError::Binary.into()),
+        }
+    }
+
+    /// Add the text to the end of the content in memory. The line ending is detected again for
+    /// the combined content.
+    ///
+    /// Binary content can't be appended to as text, so [TextDataError::Binary] is returned for it.
+    pub fn append_str(&mut self, s: &str) -> Result<(), FileError> {
+        match &mut self.content {
+            FileContent::Encoded { content } => {
+                content.data.push_str(s);
+                content.line_ending = LineEnding::detect(&content.data);
+                Ok(())
+            }
+            FileContent::Binary { .. } => Err(TextDataError::Binary.into()),
+        }
+    }
+
+    /// Whether the file has no content, as described for [FileContent::is_empty]. A file that is
+    /// only a BOM is empty.
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
+    /// The decoded content, or `None` if the content is binary.
+    pub fn text(&self) -> Option<&str> {
+        match &self.content {
+            FileContent::Encoded { content } => Some(&content.data),
+            FileContent::Binary { .. } => None,
+        }
+    }
+
+    /// Replace the content in memory with the text, keeping the [Encoding] it will be written in.
+    /// The line ending is detected again for the new content.
+    ///
+    /// Binary content has no encoding to keep, so it's replaced by the text in [Encoding::Utf8].
+    pub fn set_text(&mut self, new_text: String) {
+        let encoding = match &self.content {
+            FileContent::Encoded { content } => content.encoding,
+            FileContent::Binary { .. } => Encoding::Utf8,
+        };
+        self.content = FileContent::Encoded {
+            content: TextData::new(new_text, encoding),
+        };
+    }
+
+    /// Replace the content in memory with the result of `f` on the decoded content, keeping the
+    /// [Encoding] it will be written in. The line ending is detected again for the new content.
+    ///
+    /// Binary content has no text to map, so [TextDataError::Binary] is returned for it.
+    pub fn map_text<F: FnOnce(&str) -> String>(&mut self, f: F) -> Result<(), FileError> {
+        match &mut self.content {
+            FileContent::Encoded { content } => {
+                *content = TextData::new(f(&content.data), content.encoding);
+                Ok(())
+            }
+            FileContent::Binary { .. } => Err(TextDataError::Binary.into()),
+        }
+    }
+
+    /// Append the content to the end of the file on disk at it's [PathBuf], rather than replacing it.
+    /// If the file is missing or empty, the content is written in full, as for [File::save_to_path].
+    ///
+    /// Otherwise the encoding of the file on disk is detected from its BOM, and only the body of
+    /// the content is appended, so a BOM isn't repeated part way through the file.
+    /// [FileError::EncodingMismatch] is returned if the file has a different BOM to the content's
+    /// [Encoding], or has none when the [Encoding] does. Encodings without a BOM, like
+    /// [Encoding::Utf8] and [Encoding::Windows1252], can't be told apart on disk, so appending one
+    /// to a file in another isn't detected. Binary content is always appended as-is.
+    pub fn append_to_path(&self) -> Result<(), FileError> {
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&self.path)
+            .map_err(io_error_at(&self.path))?;
+
+        let mut prefix = Vec::with_capacity(4);
+        (&mut file)
+            .take(4)
+            .read_to_end(&mut prefix)
+            .map_err(io_error_at(&self.path))?;
+
+        let result = match &self.content {
+            FileContent::Encoded { content } if !prefix.is_empty() => {
+                let found = Encoding::from_bom(&prefix)
+                    .map(|(encoding, _)| encoding)
+                    .unwrap_or(Encoding::Utf8);
+                if found.bom() != content.encoding.bom() {
+                    return Err(FileError::EncodingMismatch {
+                        path: self.path.clone(),
+                        expected: content.encoding,
+                        found,
+                    });
+                }
+
+                self.content.write_with_bom(&mut file, false)
+            }
+            _ => self.content.write(&mut file),
+        };
+
+        result.map_err(io_error_at(&self.path))
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl File {
+    /// Read every regular file in the directory, and its subdirectories if `recursive` is true, in
+    /// parallel. The paths are collected first, in the same way as [File::read_dir], then read on
+    /// the rayon thread pool.
+    ///
+    /// The order of the results isn't deterministic, so sort them if it matters. As with
+    /// [File::read_dir], a failure to read a file or directory is returned as an `Err` item.
+    pub fn read_dir_parallel(
+        dir: impl AsRef<Path>,
+        recursive: bool,
+    ) -> Vec<Result<File, FileError>> {
+        use rayon::prelude::*;
+
+        let paths: Vec<_> = walk_dir(dir.as_ref(), recursive).collect();
+        paths
+            .into_par_iter()
+            .map(|path| path.and_then(File::new_from_path))
+            .collect()
     }
 }
 
+#[cfg(feature = "tar")]
+impl File {
+    /// Read every regular file in a tar archive, detecting the encoding of each in the same way as
+    /// [File::new], with the path of the entry in the archive as the [File]'s path. Directories,
+    /// symlinks and other special entries are skipped.
+    ///
+    /// The entries of an archive can only be read while it is borrowed, so the whole archive is
+    /// read before the iterator is returned. A failure to read an entry is yielded as an `Err`
+    /// item, and as the rest of the archive can't be found after one, nothing else is yielded.
+    pub fn read_tar(reader: impl Read) -> impl Iterator<Item = Result<File, FileError>> {
+        let mut archive = tar::Archive::new(reader);
+        let mut files = vec![];
+        let entries = match archive.entries() {
+            Ok(entries) => entries,
+            Err(e) => return vec![Err(e.into())].into_iter(),
+        };
+
+        for entry in entries {
+            let file = entry.map_err(FileError::from).and_then(|mut entry| {
+                if !entry.header().entry_type().is_file() {
+                    return Ok(None);
+                }
+                let path = entry.path()?.into_owned();
+                let mut bytes = vec![];
+                entry.read_to_end(&mut bytes).map_err(io_error_at(&path))?;
+                let content = FileContent::detect(bytes);
+                Ok(Some(File { path, content }))
+            });
+
+            match file {
+                Ok(Some(file)) => files.push(Ok(file)),
+                Ok(None) => {}
+                Err(e) => {
+                    files.push(Err(e));
+                    break;
+                }
+            }
+        }
+
+        files.into_iter()
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl File {
+    /// Create a [File] by memory-mapping the file at the given path, rather than reading it into memory.
+    /// BOM detection, binary detection and decoding all run over the mapped bytes, so text content
+    /// is decoded without first copying the raw bytes. Binary content is still copied, as
+    /// [FileContent::Binary] owns its bytes.
+    ///
+    /// Empty files can't be mapped on all platforms, so they are read normally.
+    pub fn new_from_path_mmap(path: impl Into<PathBuf>) -> Result<Self, FileError> {
+        let path = path.into();
+        check_file_path(&path)?;
+        let file = fs::File::open(&path).map_err(io_error_at(&path))?;
+        if file.metadata().map_err(io_error_at(&path))?.len() == 0 {
+            return Ok(File {
+                path,
+                content: FileContent::detect(vec![]),
+            });
+        }
+
+        // SAFETY: The mapping is only read while this function runs, and is copied into owned
+        // content before returning. As with any memory-mapped file, the content is undefined if
+        // another process modifies the file while it is mapped.
+        let bytes = unsafe { memmap2::Mmap::map(&file).map_err(io_error_at(&path))? };
+        let content = FileContent::detect_slice(&bytes);
+
+        Ok(File { path, content })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl File {
+    /// Create a [File] by asynchronously reading the content at the given path with [tokio::fs].
+    /// Only the I/O is asynchronous, the encoding is detected once all the content has been read.
+    pub async fn new_from_path_async(path: impl Into<PathBuf>) -> Result<Self, FileError> {
+        let path = path.into();
+        check_file_path(&path)?;
+        let bytes = tokio::fs::read(&path).await.map_err(io_error_at(&path))?;
+        let content = FileContent::detect(bytes);
+
+        Ok(File { path, content })
+    }
+}
+
+/// Returns a unique path for a temporary file next to the given path
+fn temp_path_for(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(
+        ".tmp-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(file_name)
+}
+
 /// Read the content and return as a [String] if it can be decoded as one of the supported encodings from [Encoding].
-pub fn read_from_reader(mut input: impl Read) -> Result<String, FileError> {
+///
+/// Any [Read] works, so piped input can be read with `read_from_reader(std::io::stdin().lock())`.
+/// Use [read_text_data_from_reader] to also learn the detected [Encoding].
+pub fn read_from_reader(input: impl Read) -> Result<String, FileError> {
+    Ok(read_text_data_from_reader(input)?.into_string())
+}
+
+/// Read the content and return it as [TextData], holding the detected [Encoding] and line ending
+/// along with the decoded content. As with [read_from_reader], this can read from stdin.
+pub fn read_text_data_from_reader(mut input: impl Read) -> Result<TextData, FileError> {
     let mut bytes = vec![];
     input.read_to_end(&mut bytes)?;
-    let text_data = TextData::try_from(bytes.as_slice())?;
-    Ok(text_data.data)
+    Ok(TextData::try_from(bytes)?)
 }
 
 /// Read the contents of a file from the given path and return as a [String] if it can be decoded as one of the supported encodings from [Encoding].
+///
+/// Unlike [std::fs::read_to_string], the BOM is stripped, so it isn't in the [String] as a leading
+/// U+FEFF. Use [read_to_string_with_bom] to keep it.
 pub fn read_to_string(path: impl AsRef<Path>) -> Result<String, FileError> {
-    Ok(TextData::try_from(path.as_ref())?.data)
+    read_to_string_with_bom(path, false)
+}
+
+/// Read the contents of a file from the given path as with [read_to_string], choosing whether to
+/// keep the BOM.
+///
+/// **When `keep_bom` is true, the BOM is kept as a leading U+FEFF (zero width no-break space),
+/// which matches [std::fs::read_to_string] for UTF-8 files.** The BOM of a UTF-16 or UTF-32 file
+/// is kept in the same way, as that is what it decodes to. When `keep_bom` is false, the BOM is
+/// stripped as it is by [read_to_string], which is what most callers that compare or parse the
+/// text want.
+pub fn read_to_string_with_bom(
+    path: impl AsRef<Path>,
+    keep_bom: bool,
+) -> Result<String, FileError> {
+    let content = TextData::try_from(path.as_ref())?;
+    if keep_bom && content.bom_bytes().is_some() {
+        Ok(format!("\u{FEFF}{}", content.data))
+    } else {
+        Ok(content.into_string())
+    }
+}
+
+/// Read the contents of a file from the given path and return them as [TextData], holding the
+/// detected [Encoding] and line ending along with the decoded content.
+pub fn read_to_text_data(path: impl AsRef<Path>) -> Result<TextData, FileError> {
+    TextData::try_from(path.as_ref())
+}
+
+/// Read the lines of the file at the given path from index `start` up to (but not including)
+/// `end`, counting from 0, without decoding the rest of the file, e.g. to show part of a large file.
+///
+/// The file is decoded incrementally with an [IncrementalDecoder], and reading stops as soon as
+/// line `end - 1` is complete. Lines are split in the same way as [TextData::lines], and fewer
+/// lines are returned if the file ends before `end`. As with [IncrementalDecoder], content without
+/// a BOM is binary if a zero-byte is found in any of the chunks that are read.
+pub fn read_line_range(
+    path: impl AsRef<Path>,
+    start: usize,
+    end: usize,
+) -> Result<Vec<String>, FileError> {
+    let path = path.as_ref();
+    check_file_path(path)?;
+    let file = fs::File::open(path).map_err(io_error_at(path))?;
+    lines_in_range(IncrementalDecoder::new(file), start, end)
+}
+
+/// Collects the lines from `start` up to `end` as described for [read_line_range], from the chunks
+/// of the decoder
+fn lines_in_range(
+    decoder: IncrementalDecoder<impl Read>,
+    start: usize,
+    end: usize,
+) -> Result<Vec<String>, FileError> {
+    if start >= end {
+        return Ok(vec![]);
+    }
+
+    let mut lines = vec![];
+    let mut line = String::new();
+    let mut index = 0;
+
+    for chunk in decoder {
+        let chunk = chunk?;
+        let mut rest = chunk.as_str();
+        while let Some(line_break) = rest.find('\n') {
+            if index >= start {
+                line.push_str(&rest[..line_break]);
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+            index += 1;
+            if index == end {
+                return Ok(lines);
+            }
+            rest = &rest[line_break + 1..];
+        }
+        if index >= start {
+            line.push_str(rest);
+        }
+    }
+
+    // The last line doesn't have a line break, unless it's empty
+    if index >= start && !line.is_empty() {
+        lines.push(line);
+    }
+    Ok(lines)
+}
+
+/// Read the file at `src`, detecting its encoding, and write its content to `dst` in the `target`
+/// [Encoding], e.g. to convert a UTF-16 file to UTF-8. `dst` can be the same path as `src`.
+///
+/// Binary content can't be converted, so it fails with [TextDataError::Binary]. Content with
+/// characters that can't be represented in the target encoding fails with an [std::io::Error] of
+/// kind [std::io::ErrorKind::InvalidData], before anything is written to `dst`.
+pub fn convert_file(
+    src: impl AsRef<Path>,
+    dst: impl AsRef<Path>,
+    target: Encoding,
+) -> Result<(), FileError> {
+    let dst = dst.as_ref();
+    let content = FileContent::Encoded {
+        content: read_to_text_data(src)?.with_encoding(target),
+    };
+    let bytes = content.to_bytes().map_err(io_error_at(dst))?;
+    fs::write(dst, bytes).map_err(io_error_at(dst))
+}
+
+/// Asynchronously read the contents of a file from the given path with [tokio::fs] and return as a [String]
+/// if it can be decoded as one of the supported encodings from [Encoding].
+#[cfg(feature = "tokio")]
+pub async fn read_to_string_async(path: impl AsRef<Path>) -> Result<String, FileError> {
+    let path = path.as_ref();
+    check_file_path(path)?;
+    let bytes = tokio::fs::read(path).await.map_err(io_error_at(path))?;
+    Ok(TextData::try_from(bytes)?.into_string())
 }
 
 #[cfg(test)]
 mod tests {
     use test_case::test_case;
 
-    use crate::encoding::Encoding;
-    use crate::file::File;
-    use crate::text_data::TextData;
+    use crate::constants::BINARY_DETECTION_THRESHOLD;
+    use crate::encoding::{to_utf16_le, Encoding, Unmappable};
+    use crate::file::{File, FileError, WriteOptions};
+    use crate::incremental::IncrementalDecoder;
+    use crate::line_ending::LineEnding;
+    use crate::text_data::{TextData, TextDataError};
     use crate::FileContent;
 
     const UTF8BOM_ASCII_CONTENT: &[u8] = include_bytes!(concat!(
@@ -137,6 +1130,10 @@ mod tests {
         env!("CARGO_MANIFEST_DIR"),
         "/tests/data/UTF16LE/ascii"
     ));
+    const BINARY_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/Binary/binary"
+    ));
 
     #[test_case(b"Hello!", Encoding::Utf8)]
     #[test_case(UTF8BOM_ASCII_CONTENT, Encoding::Utf8Bom)]
@@ -150,6 +1147,7 @@ mod tests {
                 content: TextData {
                     data: "Hello!".into(),
                     encoding,
+                    line_ending: LineEnding::None,
                 },
             },
         };
@@ -170,4 +1168,462 @@ mod tests {
 
         assert_eq!(subject, expected);
     }
+
+    #[test_case(b"Hello!"; "UTF-8")]
+    #[test_case(UTF8BOM_ASCII_CONTENT; "UTF-8 with BOM")]
+    #[test_case(UTF16BE_ASCII_CONTENT; "UTF-16BE")]
+    #[test_case(UTF16LE_ASCII_CONTENT; "UTF-16LE")]
+    #[test_case(BINARY_CONTENT; "Binary")]
+    fn from_bytes(bytes: &[u8]) {
+        let subject = File::from_bytes("foo.txt", bytes).expect("Should pass");
+
+        assert_eq!(subject, File::new("foo.txt", bytes).expect("Should pass"));
+    }
+
+    #[test]
+    fn from_bytes_binary_content() {
+        let subject = File::from_bytes("foo.bin", BINARY_CONTENT).expect("Should pass");
+
+        assert_eq!(
+            subject.content,
+            FileContent::Binary {
+                content: BINARY_CONTENT.to_vec()
+            }
+        );
+    }
+
+    #[test_case("Héllo!", 6, "Héllo!"; "At the limit")]
+    #[test_case("Héllo!", 5, "Héllo…"; "Past the limit")]
+    #[test_case("Héllo!", 0, "…"; "No chars")]
+    #[test_case("", 0, ""; "No content")]
+    fn summary_of_encoded(data: &str, max_content_chars: usize, expected: &str) {
+        let subject = File::from_string("foo.txt", data.into(), Encoding::Utf8);
+
+        assert_eq!(
+            subject.summary(max_content_chars),
+            format!("File: foo.txt\nEncoding: UTF-8\nContent:\n{expected}")
+        );
+    }
+
+    #[test_case(&[1, 2, 0, 0xFF], "01 02 00 FF (4 bytes)"; "Short")]
+    #[test_case(&[0; 16], "00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 (16 bytes)"; "At the limit")]
+    #[test_case(&[0; 17], "00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 … (17 bytes)"; "Past the limit")]
+    fn summary_of_binary(bytes: &[u8], expected: &str) {
+        let subject = File::from_bytes_binary("foo.bin", bytes.to_vec());
+
+        assert_eq!(
+            subject.summary(0),
+            format!("File: foo.bin\nEncoding: Binary\nContent:\n{expected}")
+        );
+    }
+
+    #[test_case("Café €", Ok(b"Caf\xE9 \x80".to_vec()); "mappable chars")]
+    #[test_case("Hello! 🌍", Err(std::io::ErrorKind::InvalidData); "unmappable chars")]
+    fn write_windows_1252(data: &str, expected: Result<Vec<u8>, std::io::ErrorKind>) {
+        let content = FileContent::Encoded {
+            content: TextData {
+                data: data.into(),
+                encoding: Encoding::Windows1252,
+                line_ending: LineEnding::None,
+            },
+        };
+        let mut bytes = vec![];
+        let subject = content
+            .write(&mut bytes)
+            .map(|_| bytes)
+            .map_err(|e| e.kind());
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case(Encoding::Utf8; "UTF-8")]
+    #[test_case(Encoding::Utf8Bom; "UTF-8-BOM")]
+    #[test_case(Encoding::Utf16Be; "UTF-16BE")]
+    #[test_case(Encoding::Utf32Le; "UTF-32LE")]
+    #[test_case(Encoding::Utf7; "UTF-7")]
+    fn write_counted(encoding: Encoding) {
+        let text = TextData::new("Hello! 你好! 🌍".into(), encoding);
+        let expected = text.encoded_byte_count();
+        let mut bytes = vec![];
+        let subject = FileContent::Encoded { content: text }
+            .write_counted(&mut bytes)
+            .expect("Should pass");
+
+        assert_eq!(subject, expected);
+        assert_eq!(subject, bytes.len());
+    }
+
+    #[test]
+    fn to_bytes_unmappable() {
+        let content = FileContent::Encoded {
+            content: TextData::new("€ 🌍!".into(), Encoding::Windows1252),
+        };
+        let subject = content.to_bytes().expect_err("Should fail");
+
+        assert_eq!(subject.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn hex_dump() {
+        let content = FileContent::Binary {
+            content: b"Hello, World!\n\x00\x01\xFF\x7FABC".to_vec(),
+        };
+
+        assert_eq!(
+            content.hex_dump(usize::MAX),
+            "00000000: 4865 6c6c 6f2c 2057 6f72 6c64 210a 0001  Hello, World!...\n\
+             00000010: ff7f 4142 43                             ..ABC\n"
+        );
+        assert_eq!(
+            content.hex_dump(3),
+            "00000000: 4865 6c                                  Hel\n"
+        );
+    }
+
+    #[test_case(b"\x89PNG\r\n\x1A\n\0\0\0\x0DIHDR", Some("image/png"); "PNG")]
+    #[test_case(b"%PDF-1.7\n%\xE2\xE3\xCF\xD3", Some("application/pdf"); "PDF")]
+    #[test_case(b"PK\x03\x04\x14\0\0\0", Some("application/zip"); "ZIP")]
+    #[test_case(b"PK\x05\x06\0\0\0\0", Some("application/zip"); "empty ZIP")]
+    #[test_case(b"GIF89a\x01\0\x01\0", Some("image/gif"); "GIF")]
+    #[test_case(b"\xFF\xD8\xFF\xE0\0\x10JFIF\0", Some("image/jpeg"); "JPEG")]
+    #[test_case(b"\x1F\x8B\x08\0", Some("application/gzip"); "gzip")]
+    #[test_case(b"\x89PN\0", None; "truncated magic number")]
+    #[test_case(&[1, 2, 3, 0, 4, 5], None; "unrecognised")]
+    fn sniff_mime_binary(bytes: &[u8], expected: Option<&str>) {
+        let content = FileContent::Binary {
+            content: bytes.to_vec(),
+        };
+
+        assert_eq!(content.sniff_mime(), expected);
+    }
+
+    #[test_case(Encoding::Utf8, "text/plain; charset=UTF-8")]
+    #[test_case(Encoding::Utf8Bom, "text/plain; charset=UTF-8")]
+    #[test_case(Encoding::Utf16Le, "text/plain; charset=UTF-16LE")]
+    #[test_case(Encoding::Utf32Be, "text/plain; charset=UTF-32BE")]
+    #[test_case(Encoding::Windows1252, "text/plain; charset=windows-1252")]
+    fn sniff_mime_encoded(encoding: Encoding, expected: &str) {
+        let content = FileContent::Encoded {
+            content: TextData::new("%PDF-".into(), encoding),
+        };
+
+        assert_eq!(content.sniff_mime(), Some(expected));
+    }
+
+    #[test]
+    fn hex_dump_encoded() {
+        let content = FileContent::Encoded {
+            content: TextData::new("Hi €".into(), Encoding::Utf16Le),
+        };
+
+        assert_eq!(
+            content.hex_dump(usize::MAX),
+            "00000000: fffe 4800 6900 2000 ac20                 ..H.i. .. \n"
+        );
+    }
+
+    #[test_case("Hello", Ok(b"\xC8\x85\x93\x93\x96".to_vec()); "mappable chars")]
+    #[test_case("Hello! €", Err(std::io::ErrorKind::InvalidData); "unmappable chars")]
+    fn write_cp037(data: &str, expected: Result<Vec<u8>, std::io::ErrorKind>) {
+        let content = FileContent::Encoded {
+            content: TextData::new(data.into(), Encoding::Cp037),
+        };
+        let mut bytes = vec![];
+        let subject = content
+            .write(&mut bytes)
+            .map(|_| bytes)
+            .map_err(|e| e.kind());
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case(Encoding::Utf16Le, Some(LineEnding::CrLf), b"\xFF\xFEa\x00\r\x00\n\x00b\x00\r\x00\n\x00"; "UTF-16LE as CRLF")]
+    #[test_case(Encoding::Utf16Be, Some(LineEnding::CrLf), b"\xFE\xFF\x00a\x00\r\x00\n\x00b\x00\r\x00\n"; "UTF-16BE as CRLF")]
+    #[test_case(Encoding::Utf8, Some(LineEnding::CrLf), b"a\r\nb\r\n"; "UTF-8 as CRLF")]
+    #[test_case(Encoding::Windows1252, Some(LineEnding::Lf), b"a\nb\n"; "Windows-1252 as LF")]
+    #[test_case(Encoding::Utf16Le, None, b"\xFF\xFEa\x00\n\x00b\x00\n\x00"; "UTF-16LE as-is")]
+    #[test_case(Encoding::Utf8, Some(LineEnding::Mixed), b"a\nb\n"; "Mixed as-is")]
+    fn write_with_line_ending(
+        encoding: Encoding,
+        line_ending: Option<LineEnding>,
+        expected: &[u8],
+    ) {
+        let content = FileContent::Encoded {
+            content: TextData::new("a\nb\n".into(), encoding),
+        };
+        let options = WriteOptions {
+            line_ending,
+            ..Default::default()
+        };
+        let mut bytes = vec![];
+        content
+            .write_with_options(&mut bytes, options)
+            .expect("Should pass");
+
+        assert_eq!(bytes, expected);
+        assert!(matches!(content, FileContent::Encoded { content } if content.data == "a\nb\n"));
+    }
+
+    #[test_case(Unmappable::Error, Err(std::io::ErrorKind::InvalidData); "error")]
+    #[test_case(Unmappable::Replace(b'?'), Ok(b"\x80 ?!".to_vec()); "replace")]
+    #[test_case(Unmappable::Skip, Ok(b"\x80 !".to_vec()); "skip")]
+    fn write_windows_1252_unmappable(
+        unmappable: Unmappable,
+        expected: Result<Vec<u8>, std::io::ErrorKind>,
+    ) {
+        let content = FileContent::Encoded {
+            content: TextData::new("€ 🌍!".into(), Encoding::Windows1252),
+        };
+        let options = WriteOptions {
+            unmappable,
+            ..Default::default()
+        };
+        let mut bytes = vec![];
+        let subject = content
+            .write_with_options(&mut bytes, options)
+            .map(|_| bytes)
+            .map_err(|e| e.kind());
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case(Encoding::Utf8, "", 0xCBF2_9CE4_8422_2325; "UTF-8 no content")]
+    #[test_case(Encoding::Utf8, "Hello!", 0x9224_FCE0_7C59_FABE; "UTF-8")]
+    #[test_case(Encoding::Utf8Bom, "Hello!", 0x08FF_1B7B_28DD_7D7B; "UTF-8 with BOM")]
+    fn content_hash(encoding: Encoding, data: &str, expected: u64) {
+        let content = FileContent::Encoded {
+            content: TextData::new(data.into(), encoding),
+        };
+
+        assert_eq!(content.content_hash().expect("Should pass"), expected);
+    }
+
+    #[test]
+    fn content_hash_binary() {
+        let content = FileContent::Binary {
+            content: vec![1, 2, 3, 0, 4, 5],
+        };
+
+        assert_eq!(
+            content.content_hash().expect("Should pass"),
+            0x74BF_4613_E020_475E
+        );
+    }
+
+    #[test]
+    fn content_hash_differs_across_encodings() {
+        let hashes: Vec<_> = [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be]
+            .into_iter()
+            .map(|encoding| {
+                let content = FileContent::Encoded {
+                    content: TextData::new("Hello!".into(), encoding),
+                };
+                content.content_hash().expect("Should pass")
+            })
+            .collect();
+
+        assert_ne!(hashes[0], hashes[1]);
+        assert_ne!(hashes[1], hashes[2]);
+        assert_ne!(hashes[0], hashes[2]);
+    }
+
+    #[test]
+    fn write_utf7() {
+        let content = FileContent::Encoded {
+            content: TextData::new("Hi Mom -\u{263A}-! 1 + 1 = 2".into(), Encoding::Utf7),
+        };
+        let mut bytes = vec![];
+        content.write(&mut bytes).expect("Should pass");
+
+        assert_eq!(bytes, b"Hi Mom -+Jjo--! 1 +- 1 = 2");
+    }
+
+    #[test]
+    fn convert_encoded() {
+        let mut subject = File::new("foo.txt", UTF16LE_ASCII_CONTENT).expect("Should pass");
+        subject.convert_to(Encoding::Utf8).expect("Should pass");
+
+        assert!(matches!(
+            subject.content,
+            FileContent::Encoded {
+                content: TextData {
+                    encoding: Encoding::Utf8,
+                    ..
+                }
+            }
+        ));
+    }
+
+    #[test_case(UTF8BOM_ASCII_CONTENT, UTF16BE_ASCII_CONTENT, true; "UTF-8 and UTF-16 with the same content")]
+    #[test_case(b"Hello!", UTF16LE_ASCII_CONTENT, true; "UTF-8 without a BOM and UTF-16")]
+    #[test_case(b"Hello!", b"Hello?", false; "Different content")]
+    #[test_case(&[1, 2, 3, 0], &[1, 2, 3, 0], true; "Same binary content")]
+    #[test_case(&[1, 2, 3, 0], &[1, 2, 4, 0], false; "Different binary content")]
+    #[test_case(b"Hello!", b"Hello!\0", false; "Encoded and binary content")]
+    fn content_eq(a: &[u8], b: &[u8], expected: bool) {
+        let a = File::new("a.txt", a).expect("Should pass");
+        let b = File::new("b.txt", b).expect("Should pass");
+
+        assert_eq!(a.content_eq(&b), expected);
+        assert_eq!(b.content_eq(&a), expected);
+    }
+
+    #[test]
+    fn io_error_without_path() {
+        let subject = FileError::from(std::io::Error::other("Something went wrong"));
+
+        assert_eq!(subject.to_string(), "Something went wrong");
+    }
+
+    #[test]
+    fn io_error_with_path() {
+        let subject = super::io_error_at(std::path::Path::new("foo.txt"))(std::io::Error::other(
+            "Something went wrong",
+        ));
+
+        assert_eq!(subject.to_string(), "foo.txt: Something went wrong");
+    }
+
+    #[test]
+    fn convert_binary() {
+        let bytes: &[u8] = &[1, 2, 3, 0, 4, 5];
+        let mut subject = File::new("foo.txt", bytes).expect("Should pass");
+
+        assert!(matches!(
+            subject.convert_to(Encoding::Utf8),
+            Err(FileError::TextData(TextDataError::Binary))
+        ));
+    }
+
+    #[test]
+    fn append_str() {
+        let mut subject = File::new("foo.txt", UTF16LE_ASCII_CONTENT).expect("Should pass");
+        subject.append_str("\nWorld!").expect("Should pass");
+
+        assert_eq!(
+            subject.content,
+            FileContent::Encoded {
+                content: TextData {
+                    data: "Hello!\nWorld!".into(),
+                    encoding: Encoding::Utf16Le,
+                    line_ending: LineEnding::Lf,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn append_str_binary() {
+        let bytes: &[u8] = &[1, 2, 3, 0, 4, 5];
+        let mut subject = File::new("foo.txt", bytes).expect("Should pass");
+
+        assert!(matches!(
+            subject.append_str("Hello!"),
+            Err(FileError::TextData(TextDataError::Binary))
+        ));
+    }
+
+    #[test]
+    fn text() {
+        let encoded = File::new("foo.txt", UTF16LE_ASCII_CONTENT).expect("Should pass");
+        let binary = File::from_bytes_binary("foo.bin", vec![1, 2, 3, 0]);
+
+        assert_eq!(encoded.text(), Some("Hello!"));
+        assert_eq!(binary.text(), None);
+    }
+
+    #[test_case(UTF16LE_ASCII_CONTENT, Encoding::Utf16Le; "encoded")]
+    #[test_case(&[1, 2, 3, 0], Encoding::Utf8; "binary")]
+    fn set_text(bytes: &[u8], encoding: Encoding) {
+        let mut subject = File::new("foo.txt", bytes).expect("Should pass");
+        subject.set_text("Hello,\r\nWorld!".into());
+
+        assert_eq!(
+            subject.content,
+            FileContent::Encoded {
+                content: TextData {
+                    data: "Hello,\r\nWorld!".into(),
+                    encoding,
+                    line_ending: LineEnding::CrLf,
+                },
+            }
+        );
+    }
+
+    #[test_case(vec![], true; "No bytes")]
+    #[test_case(vec![0], false; "Zero-byte")]
+    fn binary_is_empty(bytes: Vec<u8>, expected: bool) {
+        let subject = File::from_bytes_binary("foo.bin", bytes);
+
+        assert_eq!(subject.is_empty(), expected);
+    }
+
+    #[test]
+    fn map_text_binary() {
+        let mut subject = File::from_bytes_binary("foo.bin", vec![1, 2, 3, 0]);
+
+        assert!(matches!(
+            subject.map_text(str::to_uppercase),
+            Err(FileError::TextData(TextDataError::Binary))
+        ));
+    }
+
+    #[test_case(Encoding::Utf8, "Hello!", true, b"\xEF\xBB\xBFHello!"; "UTF-8 with BOM")]
+    #[test_case(Encoding::Utf8, "Hello!", false, b"Hello!"; "UTF-8 without BOM")]
+    #[test_case(Encoding::Utf8Bom, "Hello!", true, b"\xEF\xBB\xBFHello!"; "UTF-8-BOM with BOM")]
+    #[test_case(Encoding::Utf8Bom, "Hello!", false, b"Hello!"; "UTF-8-BOM without BOM")]
+    #[test_case(Encoding::Utf16Le, "Hi", true, b"\xFF\xFEH\x00i\x00"; "UTF-16LE with BOM")]
+    #[test_case(Encoding::Utf16Be, "Hi", false, b"\x00H\x00i"; "UTF-16BE without BOM")]
+    #[test_case(Encoding::Utf7, "Hi", true, b"Hi"; "UTF-7 has no BOM")]
+    #[test_case(Encoding::Windows1252, "Café", true, b"Caf\xE9"; "Windows-1252 has no BOM")]
+    fn write_with_bom(encoding: Encoding, data: &str, include_bom: bool, expected: &[u8]) {
+        let content = FileContent::Encoded {
+            content: TextData::new(data.into(), encoding),
+        };
+        let mut bytes = vec![];
+        content
+            .write_with_bom(&mut bytes, include_bom)
+            .expect("Should pass");
+
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn write_binary_with_bom() {
+        let content = FileContent::Binary {
+            content: vec![1, 2, 3, 0, 4, 5],
+        };
+        let mut bytes = vec![];
+        content
+            .write_with_bom(&mut bytes, true)
+            .expect("Should pass");
+
+        assert_eq!(bytes, [1, 2, 3, 0, 4, 5]);
+    }
+
+    #[test_case(0, 2, &["zero", "one"]; "First lines")]
+    #[test_case(2, 4, &["二", "three 🌍"]; "Middle lines")]
+    #[test_case(4, 10, &["", "five"]; "Past the end")]
+    #[test_case(6, 10, &[]; "Start past the end")]
+    #[test_case(3, 3, &[]; "Empty range")]
+    fn lines_in_range(start: usize, end: usize, expected: &[&str]) {
+        let bytes = to_utf16_le("zero\r\none\n二\nthree 🌍\r\n\nfive");
+        let decoder = IncrementalDecoder::with_chunk_size(bytes.as_slice(), 3);
+
+        let subject = super::lines_in_range(decoder, start, end).expect("Should pass");
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test]
+    fn lines_in_range_stops_early() {
+        // The invalid byte after the binary detection window would fail to decode if it were read
+        let mut bytes = "line\n".repeat(BINARY_DETECTION_THRESHOLD).into_bytes();
+        bytes.push(0xC1);
+        let decoder = IncrementalDecoder::with_chunk_size(bytes.as_slice(), 1024);
+
+        let subject = super::lines_in_range(decoder, 1, 3).expect("Should pass");
+
+        assert_eq!(subject, ["line", "line"]);
+    }
 }
@@ -0,0 +1,135 @@
+use std::path::Path;
+
+/// The `text` attributes from a `.gitattributes` file, used by [crate::File::new_with_gitattributes]
+/// to force paths to be read as text or binary, as Git does, rather than detecting it from their
+/// content.
+///
+/// Only the attributes that decide whether a path is text are parsed: `text` forces a path to
+/// text, while `-text` and the `binary` macro force it to binary. Every other attribute is
+/// ignored, as are `text=auto` and `!text`, which leave it to be detected from the content.
+/// As in Git, when several patterns match a path the last one in the file wins.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct GitAttributes {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Rule {
+    pattern: String,
+    is_text: Option<bool>,
+}
+
+impl GitAttributes {
+    /// Parse the content of a `.gitattributes` file. Blank lines and lines starting with `#` are
+    /// skipped, and each other line is a pattern followed by its attributes.
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?;
+                let is_text = fields.fold(None, |is_text, attribute| match attribute {
+                    "text" => Some(Some(true)),
+                    "-text" | "binary" => Some(Some(false)),
+                    "!text" | "text=auto" => Some(None),
+                    _ => is_text,
+                })?;
+
+                Some(Rule {
+                    pattern: pattern.strip_prefix('/').unwrap_or(pattern).to_string(),
+                    is_text,
+                })
+            })
+            .collect();
+
+        GitAttributes { rules }
+    }
+
+    /// Whether the path is forced to be text (`Some(true)`) or binary (`Some(false)`), or `None`
+    /// if it's left to be detected from the content.
+    ///
+    /// Patterns without a `/` match the file name, and `*` and `?` don't match a `/`, so `*.bin`
+    /// matches `a/b.bin`. Patterns with a `/` match the whole path, which should be relative to the
+    /// directory of the `.gitattributes` file, and `**` matches any number of directories in them.
+    pub fn is_text(&self, path: impl AsRef<Path>) -> Option<bool> {
+        let path = path.as_ref();
+        let full_path = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| match rule.pattern.contains('/') {
+                true => glob_matches(rule.pattern.as_bytes(), full_path.as_bytes()),
+                false => glob_matches(rule.pattern.as_bytes(), file_name.as_bytes()),
+            })
+            .and_then(|rule| rule.is_text)
+    }
+}
+
+/// Whether the path matches the glob pattern, where `*` and `?` match within a single path
+/// component and `**` matches across components
+fn glob_matches(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        [b'*', b'*', b'/', rest @ ..] => {
+            glob_matches(rest, path)
+                || path
+                    .iter()
+                    .enumerate()
+                    .any(|(i, byte)| *byte == b'/' && glob_matches(rest, &path[i + 1..]))
+        }
+        [b'*', b'*', rest @ ..] => (0..=path.len()).any(|i| glob_matches(rest, &path[i..])),
+        [b'*', rest @ ..] => (0..=path.len())
+            .take_while(|i| *i == 0 || path[i - 1] != b'/')
+            .any(|i| glob_matches(rest, &path[i..])),
+        [b'?', rest @ ..] => {
+            matches!(path, [first, ..] if *first != b'/') && glob_matches(rest, &path[1..])
+        }
+        [expected, rest @ ..] => path.first() == Some(expected) && glob_matches(rest, &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::gitattributes::{glob_matches, GitAttributes};
+
+    #[test_case("*.bin", "data.bin", true; "Star")]
+    #[test_case("*.bin", "data.txt", false; "Star mismatch")]
+    #[test_case("data.?in", "data.bin", true; "Question mark")]
+    #[test_case("docs/*.md", "docs/a.md", true; "Directory")]
+    #[test_case("docs/*.md", "docs/a/b.md", false; "Star across directories")]
+    #[test_case("docs/**/*.md", "docs/a/b.md", true; "Double star")]
+    #[test_case("docs/**/*.md", "docs/b.md", true; "Double star matching nothing")]
+    #[test_case("**/*.md", "a/b/c.md", true; "Leading double star")]
+    #[test_case("docs/**", "docs/a/b.md", true; "Trailing double star")]
+    fn glob(pattern: &str, path: &str, expected: bool) {
+        assert_eq!(glob_matches(pattern.as_bytes(), path.as_bytes()), expected);
+    }
+
+    #[test_case("a/data.bin", Some(false); "Binary by name")]
+    #[test_case("data.dat", Some(false); "Unset text")]
+    #[test_case("data.txt", Some(true); "Text")]
+    #[test_case("fixtures/data.txt", Some(false); "Later rule wins")]
+    #[test_case("data.auto", None; "Auto")]
+    #[test_case("data.md", None; "Other attributes")]
+    #[test_case("data.rs", None; "No match")]
+    fn is_text(path: &str, expected: Option<bool>) {
+        let subject = GitAttributes::parse(
+            "# Comment\n\n*.bin binary\n*.dat -text\n*.txt text eol=lf\n/fixtures/** -diff -text\n*.auto text=auto\n*.md diff\n",
+        );
+
+        assert_eq!(subject.is_text(path), expected);
+    }
+}
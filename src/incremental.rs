@@ -0,0 +1,328 @@
+use std::io::Read;
+
+use crate::constants::{BINARY_DETECTION_THRESHOLD, ZERO_BYTE};
+use crate::encoding::Encoding;
+use crate::text_data::{self, check_utf16_strictness, decode, TextDataError, TextDataOptions};
+use crate::FileError;
+
+/// The number of bytes read from the underlying reader at a time, unless configured otherwise.
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Decodes the content of a [Read] in chunks, without holding all of its bytes in memory at once.
+///
+/// The encoding is detected in the same way as [crate::TextData] with the default
+/// [TextDataOptions], from its first 8 thousand bytes, so they are read before the first chunk is
+/// decoded. E.g. a truncated or repeated BOM is an error. Unlike [crate::TextData], content without
+/// a BOM is binary if a zero-byte is found in any chunk, not just its first 8 thousand bytes.
+/// Multi-byte characters (and UTF-16 surrogate pairs) that are split across reads are held back
+/// until the rest of the character has been read, so every chunk that is returned is complete.
+pub struct IncrementalDecoder<R: Read> {
+    reader: R,
+    chunk_size: usize,
+    encoding: Option<Encoding>,
+    // Bytes that have been read but not yet decoded
+    pending: Vec<u8>,
+    // Bytes that have been decoded (or skipped as a BOM), used to report the offset of errors
+    consumed: usize,
+    finished: bool,
+}
+
+impl<R: Read> IncrementalDecoder<R> {
+    /// Create an [IncrementalDecoder] that reads from the given input 64KiB at a time.
+    pub fn new(input: R) -> Self {
+        Self::with_chunk_size(input, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Create an [IncrementalDecoder] that reads from the given input `chunk_size` bytes at a time.
+    pub fn with_chunk_size(input: R, chunk_size: usize) -> Self {
+        IncrementalDecoder {
+            reader: input,
+            chunk_size: chunk_size.max(1),
+            encoding: None,
+            pending: vec![],
+            consumed: 0,
+            finished: false,
+        }
+    }
+
+    /// The detected encoding, which is known once the first chunk has been read.
+    pub fn encoding(&self) -> Option<Encoding> {
+        self.encoding
+    }
+
+    /// Read and decode the next chunk of content.
+    /// Returns `None` once all of the content has been decoded, or after an error has been returned.
+    pub fn read_chunk(&mut self) -> Result<Option<String>, FileError> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let result = self.decode_next_chunk();
+        if !matches!(result, Ok(Some(_))) {
+            self.finished = true;
+        }
+
+        result
+    }
+
+    fn decode_next_chunk(&mut self) -> Result<Option<String>, FileError> {
+        loop {
+            let end_of_input = self.fill_pending()?;
+            let encoding = match self.encoding {
+                Some(encoding) => encoding,
+                None => self.detect_encoding()?,
+            };
+
+            if encoding == Encoding::Utf8 && self.pending.contains(&ZERO_BYTE) {
+                return Err(TextDataError::Binary.into());
+            }
+
+            let length = if end_of_input {
+                self.pending.len()
+            } else {
+                complete_length(&self.pending, encoding)
+            };
+
+            if length > 0 {
+                let data = decode(&self.pending[..length], encoding)
+                    .and_then(|data| {
+                        check_utf16_strictness(&data, encoding, TextDataOptions::default())?;
+                        Ok(data)
+                    })
+                    .map_err(|e| e.offset_by(self.consumed))?;
+                self.pending.drain(..length);
+                self.consumed += length;
+                return Ok(Some(data));
+            } else if end_of_input {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Reads the next chunk of bytes into the pending buffer, returning true if the input has ended.
+    /// Until the encoding is known, this reads until there are enough bytes to detect it, including
+    /// checking whether the content is binary.
+    fn fill_pending(&mut self) -> Result<bool, std::io::Error> {
+        let mut buffer = vec![0; self.chunk_size];
+        loop {
+            let read = match self.reader.read(&mut buffer) {
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            self.pending.extend_from_slice(&buffer[..read]);
+
+            if read == 0 {
+                return Ok(true);
+            } else if self.encoding.is_some() || self.pending.len() >= BINARY_DETECTION_THRESHOLD {
+                return Ok(false);
+            }
+        }
+    }
+
+    fn detect_encoding(&mut self) -> Result<Encoding, TextDataError> {
+        let (encoding, bom_length) =
+            text_data::detect_encoding(&self.pending, TextDataOptions::default())?;
+        self.pending.drain(..bom_length);
+        self.consumed += bom_length;
+        self.encoding = Some(encoding);
+        Ok(encoding)
+    }
+}
+
+impl<R: Read> Iterator for IncrementalDecoder<R> {
+    type Item = Result<String, FileError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_chunk().transpose()
+    }
+}
+
+/// Returns the length of the longest prefix of the bytes that doesn't end part way through a character.
+/// Invalid sequences are included in the prefix, so that they are reported when it's decoded.
+fn complete_length(bytes: &[u8], encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Utf8 | Encoding::Utf8Bom => match std::str::from_utf8(bytes) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => bytes.len(),
+        },
+        Encoding::Utf16Be | Encoding::Utf16Le => {
+            let length = bytes.len() - bytes.len() % 2;
+            let last_unit = match (encoding, &bytes[..length]) {
+                (Encoding::Utf16Be, [.., high, low]) => u16::from_be_bytes([*high, *low]),
+                (_, [.., low, high]) => u16::from_le_bytes([*low, *high]),
+                _ => return length,
+            };
+
+            // Hold back a leading surrogate until the trailing surrogate has been read
+            if (0xD800..=0xDBFF).contains(&last_unit) {
+                length - 2
+            } else {
+                length
+            }
+        }
+        Encoding::Utf32Be | Encoding::Utf32Le => bytes.len() - bytes.len() % 4,
+        // Hold back a shifted sequence until the byte that ends it has been read
+        Encoding::Utf7 => match bytes.iter().rposition(|byte| *byte == b'+') {
+            Some(start)
+                if bytes[start + 1..]
+                    .iter()
+                    .all(|byte| byte.is_ascii_alphanumeric() || *byte == b'/') =>
+            {
+                start
+            }
+            _ => bytes.len(),
+        },
+        Encoding::Windows1252 | Encoding::Cp037 => bytes.len(),
+        // Never detected from a BOM, so this isn't decoded incrementally
+        #[cfg(feature = "encoding_rs")]
+        Encoding::Other(_) => bytes.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use test_case::test_case;
+
+    use crate::{
+        constants::BINARY_DETECTION_THRESHOLD, encoding::to_utf16_le,
+        incremental::IncrementalDecoder, Encoding, FileError, TextDataError,
+    };
+
+    const FILE_CONTENT: &str = "Hello! 你好! 🌍";
+
+    /// A reader that returns a single byte from each call to read
+    struct OneByteReader<'a>(&'a [u8]);
+
+    impl Read for OneByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.split_first() {
+                Some((byte, rest)) if !buf.is_empty() => {
+                    buf[0] = *byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test_case("UTF8/unicode", Encoding::Utf8)]
+    #[test_case("UTF8BOM/unicode", Encoding::Utf8Bom)]
+    #[test_case("UTF16BE/unicode", Encoding::Utf16Be)]
+    #[test_case("UTF16LE/unicode", Encoding::Utf16Le)]
+    #[test_case("UTF32BE/unicode", Encoding::Utf32Be)]
+    #[test_case("UTF32LE/unicode", Encoding::Utf32Le)]
+    fn decode_one_byte_at_a_time(path: &str, encoding: Encoding) {
+        let bytes = std::fs::read(format!("{}/tests/data/{path}", env!("CARGO_MANIFEST_DIR")))
+            .expect("Should read fixture");
+        let mut subject = IncrementalDecoder::new(OneByteReader(&bytes));
+        let chunks = subject
+            .by_ref()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Should pass");
+
+        assert_eq!(chunks.concat(), FILE_CONTENT);
+        assert!(chunks.iter().all(|chunk| !chunk.is_empty()));
+        assert_eq!(subject.encoding(), Some(encoding));
+    }
+
+    #[test_case(1; "1 byte chunks")]
+    #[test_case(3; "3 byte chunks")]
+    #[test_case(1024; "chunks larger than the content")]
+    fn decode_in_chunks(chunk_size: usize) {
+        let bytes = to_utf16_le(&"🌍".repeat(10));
+        let subject = IncrementalDecoder::with_chunk_size(bytes.as_slice(), chunk_size);
+        let content = subject.collect::<Result<String, _>>().expect("Should pass");
+
+        assert_eq!(content, "🌍".repeat(10));
+    }
+
+    #[test]
+    fn decode_utf7_in_chunks() {
+        let mut subject = IncrementalDecoder::with_chunk_size(b"Hi Mom -+Jjo--!".as_slice(), 1);
+        subject.encoding = Some(Encoding::Utf7);
+        let content = subject.collect::<Result<String, _>>().expect("Should pass");
+
+        assert_eq!(content, "Hi Mom -\u{263A}-!");
+    }
+
+    #[test]
+    fn decode_empty() {
+        let mut subject = IncrementalDecoder::new(b"".as_slice());
+
+        assert!(matches!(subject.read_chunk(), Ok(None)));
+        assert_eq!(subject.encoding(), Some(Encoding::Utf8));
+    }
+
+    #[test]
+    fn decode_binary() {
+        let subject = IncrementalDecoder::new(OneByteReader(b"Hello!\0"));
+        let result = subject.collect::<Result<String, _>>();
+
+        assert!(matches!(
+            result,
+            Err(FileError::TextData(TextDataError::Binary))
+        ));
+    }
+
+    #[test]
+    fn decode_zero_byte_past_binary_threshold() {
+        let mut bytes = b"a".repeat(BINARY_DETECTION_THRESHOLD);
+        bytes.push(0);
+        let subject = IncrementalDecoder::with_chunk_size(bytes.as_slice(), 1024);
+
+        assert!(matches!(
+            subject.collect::<Result<String, _>>(),
+            Err(FileError::TextData(TextDataError::Binary))
+        ));
+    }
+
+    #[test]
+    fn decode_noncharacter() {
+        let bytes = b"\xFE\xFF\x00H\xFF\xFE";
+        let subject = IncrementalDecoder::new(OneByteReader(bytes))
+            .collect::<Result<String, _>>()
+            .expect("Should pass");
+
+        assert_eq!(subject, "H\u{FFFE}");
+        assert_eq!(
+            crate::TextData::try_from(bytes.as_slice())
+                .expect("Should pass")
+                .data,
+            subject
+        );
+    }
+
+    #[test_case(b"\xFF", "Content is a truncated UTF-16-LE BOM"; "Truncated BOM")]
+    #[test_case(b"\xEF\xBB\xBF\xEF\xBB\xBFHello", "Content starts with more than one BOM: UTF-8-BOM followed by UTF-8-BOM"; "Multiple BOMs")]
+    fn decode_same_as_text_data(bytes: &[u8], message: &str) {
+        let subject = IncrementalDecoder::new(OneByteReader(bytes))
+            .collect::<Result<String, _>>()
+            .expect_err("Should fail");
+
+        assert_eq!(subject.to_string(), message);
+        assert_eq!(
+            crate::TextData::try_from(bytes)
+                .expect_err("Should fail")
+                .to_string(),
+            message
+        );
+    }
+
+    #[test_case(b"\xEF\xBB\xBFHello\xC1\x80", 8; "Invalid UTF-8 sequence")]
+    #[test_case(b"Hello\xE0\xA4", 5; "Incomplete UTF-8 sequence at the end")]
+    #[test_case(b"\xFF\xFEH\x00\x3D\xD8", 4; "Incomplete UTF-16 surrogate pair at the end")]
+    fn decode_invalid(bytes: &[u8], offset: usize) {
+        let mut subject = IncrementalDecoder::new(OneByteReader(bytes));
+        let result = subject.by_ref().collect::<Result<String, _>>();
+
+        match result {
+            Err(FileError::TextData(e)) => assert_eq!(e.offset(), Some(offset)),
+            _ => panic!("Should fail with a decoding error"),
+        }
+        assert!(subject.next().is_none());
+    }
+}
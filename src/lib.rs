@@ -1,14 +1,55 @@
+mod binary;
 mod constants;
+mod cp037;
 mod encoding;
+mod encoding_writer;
 mod file;
+mod gitattributes;
+mod incremental;
+mod line_ending;
+#[cfg(feature = "unicode-normalization")]
+mod normalization;
+mod script;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod text_data;
 mod utf16;
+mod utf32;
+mod utf7;
+mod windows1252;
 
+pub use binary::BinaryHeuristic;
+pub use binary::BinaryVerdict;
 pub use encoding::Encoding;
+pub use encoding::ParseEncodingError;
+pub use encoding::Unmappable;
+pub use encoding::UnmappableCharError;
+pub use encoding_writer::EncodingWriter;
+pub use file::convert_file;
 pub use file::read_from_reader;
+pub use file::read_line_range;
+pub use file::read_text_data_from_reader;
 pub use file::read_to_string;
+#[cfg(feature = "tokio")]
+pub use file::read_to_string_async;
+pub use file::read_to_string_with_bom;
+pub use file::read_to_text_data;
 pub use file::File;
 pub use file::FileContent;
 pub use file::FileError;
+pub use file::WriteOptions;
+pub use gitattributes::GitAttributes;
+pub use incremental::IncrementalDecoder;
+pub use line_ending::LineEnding;
+#[cfg(feature = "unicode-normalization")]
+pub use normalization::NormalizationForm;
+pub use script::Script;
+pub use text_data::BomRepair;
+pub use text_data::BorrowedTextData;
+pub use text_data::DecodeReport;
 pub use text_data::TextData;
 pub use text_data::TextDataError;
+pub use text_data::TextDataOptions;
+pub use text_data::Utf16Strictness;
@@ -0,0 +1,94 @@
+use std::{borrow::Cow, fmt::Display};
+
+/// Represents the style of line breaks used in decoded text.
+///
+/// Only `\n` and `\r\n` are recognised as line breaks, a lone `\r` is treated as regular content.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineEnding {
+    /// Every line break is `\n`
+    Lf,
+    /// Every line break is `\r\n`
+    CrLf,
+    /// Both `\n` and `\r\n` line breaks are present
+    Mixed,
+    /// There are no line breaks
+    None,
+}
+
+impl LineEnding {
+    /// Detect the style of line breaks used in the given text
+    pub fn detect(s: &str) -> LineEnding {
+        let line_breaks = s.matches('\n').count();
+        let crlf_line_breaks = s.matches("\r\n").count();
+
+        match (line_breaks, crlf_line_breaks) {
+            (0, _) => LineEnding::None,
+            (lf, crlf) if lf == crlf => LineEnding::CrLf,
+            (_, 0) => LineEnding::Lf,
+            _ => LineEnding::Mixed,
+        }
+    }
+
+    /// Rewrite every line break in the text to use this style, borrowing the text if it's left
+    /// as-is. [LineEnding::Mixed] and [LineEnding::None] aren't styles that can be written, so the
+    /// text is left as-is for them.
+    pub(crate) fn convert(self, s: &str) -> Cow<'_, str> {
+        match self {
+            LineEnding::Lf if s.contains("\r\n") => Cow::Owned(s.replace("\r\n", "\n")),
+            LineEnding::CrLf
+                if matches!(LineEnding::detect(s), LineEnding::Lf | LineEnding::Mixed) =>
+            {
+                Cow::Owned(s.replace("\r\n", "\n").replace('\n', "\r\n"))
+            }
+            _ => Cow::Borrowed(s),
+        }
+    }
+}
+
+impl Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LineEnding::Lf => write!(f, "LF"),
+            LineEnding::CrLf => write!(f, "CRLF"),
+            LineEnding::Mixed => write!(f, "Mixed"),
+            LineEnding::None => write!(f, "None"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use test_case::test_case;
+
+    use crate::line_ending::LineEnding;
+
+    #[test_case("", LineEnding::None; "no content")]
+    #[test_case("Hello!", LineEnding::None; "no line breaks")]
+    #[test_case("Hello!\rWorld!", LineEnding::None; "lone carriage return")]
+    #[test_case("Hello!\nWorld!\n", LineEnding::Lf; "LF")]
+    #[test_case("Hello!\r\nWorld!\r\n", LineEnding::CrLf; "CRLF")]
+    #[test_case("\r\n", LineEnding::CrLf; "only CRLF")]
+    #[test_case("Hello!\r\nWorld!\n", LineEnding::Mixed; "mixed")]
+    fn detect(input: &str, expected: LineEnding) {
+        assert_eq!(LineEnding::detect(input), expected);
+    }
+
+    #[test_case("a\nb\r\nc", LineEnding::Lf, "a\nb\nc"; "to LF")]
+    #[test_case("a\nb\r\nc", LineEnding::CrLf, "a\r\nb\r\nc"; "to CRLF")]
+    #[test_case("a\rb\n", LineEnding::CrLf, "a\rb\r\n"; "lone carriage return")]
+    #[test_case("a\nb\r\nc", LineEnding::Mixed, "a\nb\r\nc"; "Mixed is a no-op")]
+    #[test_case("a\nb\r\nc", LineEnding::None, "a\nb\r\nc"; "None is a no-op")]
+    fn convert(input: &str, line_ending: LineEnding, expected: &str) {
+        assert_eq!(line_ending.convert(input), expected);
+    }
+
+    #[test_case("a\nb", LineEnding::Lf; "already LF")]
+    #[test_case("a\r\nb", LineEnding::CrLf; "already CRLF")]
+    #[test_case("a", LineEnding::CrLf; "no line breaks")]
+    fn convert_borrows_unchanged(input: &str, line_ending: LineEnding) {
+        assert!(matches!(line_ending.convert(input), Cow::Borrowed(_)));
+    }
+}
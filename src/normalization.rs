@@ -0,0 +1,65 @@
+use unicode_normalization::UnicodeNormalization;
+
+use crate::TextData;
+
+/// The Unicode normalization forms that [TextData::normalize] can rewrite content into
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NormalizationForm {
+    /// Canonical composition, e.g. "e" followed by a combining acute accent becomes "é"
+    Nfc,
+    /// Canonical decomposition, e.g. "é" becomes "e" followed by a combining acute accent
+    Nfd,
+    /// Compatibility composition, which also replaces compatibility characters, e.g. "ﬁ" becomes "fi"
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+impl TextData {
+    /// Rewrite the content in the given Unicode [NormalizationForm], e.g. to compare text from
+    /// macOS, which is often decomposed (NFD), with text that is composed (NFC).
+    /// The [crate::Encoding] is left unchanged.
+    pub fn normalize(&mut self, form: NormalizationForm) {
+        self.data = match form {
+            NormalizationForm::Nfc => self.data.nfc().collect(),
+            NormalizationForm::Nfd => self.data.nfd().collect(),
+            NormalizationForm::Nfkc => self.data.nfkc().collect(),
+            NormalizationForm::Nfkd => self.data.nfkd().collect(),
+        };
+    }
+
+    /// Whether the content is already in [NormalizationForm::Nfc], so normalizing it to NFC
+    /// wouldn't change it
+    pub fn is_nfc(&self) -> bool {
+        unicode_normalization::is_nfc(&self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::{normalization::NormalizationForm, Encoding, TextData};
+
+    const DECOMPOSED: &str = "Cafe\u{301} \u{FB01}";
+    const COMPOSED: &str = "Caf\u{E9} \u{FB01}";
+
+    #[test_case(DECOMPOSED, NormalizationForm::Nfc, COMPOSED; "NFC composes")]
+    #[test_case(COMPOSED, NormalizationForm::Nfd, DECOMPOSED; "NFD decomposes")]
+    #[test_case(DECOMPOSED, NormalizationForm::Nfkc, "Caf\u{E9} fi"; "NFKC replaces compatibility chars")]
+    #[test_case(COMPOSED, NormalizationForm::Nfkd, "Cafe\u{301} fi"; "NFKD replaces compatibility chars")]
+    fn normalize(data: &str, form: NormalizationForm, expected: &str) {
+        let mut subject = TextData::new(data.into(), Encoding::Utf16Le);
+        subject.normalize(form);
+
+        assert_eq!(subject.data, expected);
+        assert_eq!(subject.encoding, Encoding::Utf16Le);
+    }
+
+    #[test_case(COMPOSED, true; "composed")]
+    #[test_case(DECOMPOSED, false; "decomposed")]
+    #[test_case("Hello!", true; "ASCII")]
+    fn is_nfc(data: &str, expected: bool) {
+        assert_eq!(TextData::from(data).is_nfc(), expected);
+    }
+}
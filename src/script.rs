@@ -0,0 +1,110 @@
+/// A coarse grouping of the writing systems that text can be in, as returned by
+/// [crate::TextData::dominant_script]. It's only a hint of the language of the text, as most
+/// scripts are used by many languages.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    /// Chinese, Japanese and Korean, including Kana and Hangul
+    Cjk,
+    Arabic,
+    Hebrew,
+    /// Letters from any other script
+    Other,
+}
+
+/// Every [Script], in the order ties between them are broken in
+const SCRIPTS: [Script; 7] = [
+    Script::Latin,
+    Script::Cyrillic,
+    Script::Greek,
+    Script::Cjk,
+    Script::Arabic,
+    Script::Hebrew,
+    Script::Other,
+];
+
+impl Script {
+    /// The script of the character, or `None` if it isn't a letter, e.g. for digits, punctuation
+    /// and whitespace, which are shared between scripts.
+    pub fn of(c: char) -> Option<Script> {
+        if !c.is_alphabetic() {
+            return None;
+        }
+
+        Some(match c as u32 {
+            0x0041..=0x024F | 0x1E00..=0x1EFF | 0x2C60..=0x2C7F | 0xA720..=0xA7FF => Script::Latin,
+            0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+            0x0400..=0x052F | 0x1C80..=0x1C8F | 0x2DE0..=0x2DFF | 0xA640..=0xA69F => {
+                Script::Cyrillic
+            }
+            0x0590..=0x05FF | 0xFB1D..=0xFB4F => Script::Hebrew,
+            0x0600..=0x06FF
+            | 0x0750..=0x077F
+            | 0x08A0..=0x08FF
+            | 0xFB50..=0xFDFF
+            | 0xFE70..=0xFEFF => Script::Arabic,
+            0x1100..=0x11FF
+            | 0x2E80..=0x2FDF
+            | 0x3000..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA960..=0xA97F
+            | 0xAC00..=0xD7FF
+            | 0xF900..=0xFAFF
+            | 0xFF66..=0xFFDC
+            | 0x20000..=0x3FFFF => Script::Cjk,
+            _ => Script::Other,
+        })
+    }
+
+    /// The script most of the letters in the text are in, or `None` if it has no letters.
+    /// Ties are broken in the order the variants of [Script] are declared in.
+    pub(crate) fn dominant(s: &str) -> Option<Script> {
+        let mut counts = [0usize; SCRIPTS.len()];
+        for script in s.chars().filter_map(Script::of) {
+            counts[script as usize] += 1;
+        }
+
+        // The last of the most common is returned, so reverse to break ties in declaration order
+        let (index, count) = counts
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, count)| **count)?;
+        (*count > 0).then_some(SCRIPTS[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::script::Script;
+
+    #[test_case('a', Some(Script::Latin); "ascii letter")]
+    #[test_case('é', Some(Script::Latin); "latin-1 letter")]
+    #[test_case('ж', Some(Script::Cyrillic); "cyrillic")]
+    #[test_case('λ', Some(Script::Greek); "greek")]
+    #[test_case('你', Some(Script::Cjk); "han")]
+    #[test_case('か', Some(Script::Cjk); "hiragana")]
+    #[test_case('한', Some(Script::Cjk); "hangul")]
+    #[test_case('ب', Some(Script::Arabic); "arabic")]
+    #[test_case('ש', Some(Script::Hebrew); "hebrew")]
+    #[test_case('क', Some(Script::Other); "devanagari")]
+    #[test_case('1', None; "digit")]
+    #[test_case('!', None; "punctuation")]
+    #[test_case(' ', None; "whitespace")]
+    #[test_case('🌍', None; "emoji")]
+    fn of(c: char, expected: Option<Script>) {
+        assert_eq!(Script::of(c), expected);
+    }
+
+    #[test_case("ab вг", Script::Latin; "tie")]
+    #[test_case("a вг", Script::Cyrillic; "majority")]
+    fn dominant(s: &str, expected: Script) {
+        assert_eq!(Script::dominant(s), Some(expected));
+    }
+}
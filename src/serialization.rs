@@ -0,0 +1,118 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::encoding::Encoding;
+
+/// [Encoding] is serialized as its human readable name, e.g. "UTF-16-LE"
+impl Serialize for Encoding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Encoding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(D::Error::custom)
+    }
+}
+
+/// (De)serializes bytes as a base64 string, so binary content can be held in text formats like JSON
+pub mod base64_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::{Encoding, File, FileContent, LineEnding, TextData};
+
+    #[test_case(Encoding::Utf8, "\"UTF-8\"")]
+    #[test_case(Encoding::Utf8Bom, "\"UTF-8-BOM\"")]
+    #[test_case(Encoding::Utf16Be, "\"UTF-16-BE\"")]
+    #[test_case(Encoding::Utf16Le, "\"UTF-16-LE\"")]
+    #[test_case(Encoding::Utf32Be, "\"UTF-32-BE\"")]
+    #[test_case(Encoding::Utf32Le, "\"UTF-32-LE\"")]
+    #[test_case(Encoding::Utf7, "\"UTF-7\"")]
+    #[test_case(Encoding::Windows1252, "\"Windows-1252\"")]
+    fn encoding_round_trip(encoding: Encoding, json: &str) {
+        assert_eq!(serde_json::to_string(&encoding).expect("Should pass"), json);
+        assert_eq!(
+            serde_json::from_str::<Encoding>(json).expect("Should pass"),
+            encoding
+        );
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn other_encoding_round_trip() {
+        let json = serde_json::to_string(&Encoding::Other("Shift_JIS")).expect("Should pass");
+
+        assert_eq!(json, "\"Shift_JIS\"");
+        assert_eq!(
+            serde_json::from_str::<Encoding>(&json).expect("Should pass"),
+            Encoding::Other("Shift_JIS")
+        );
+    }
+
+    #[test]
+    fn unknown_encoding() {
+        let subject = serde_json::from_str::<Encoding>("\"UTF-9\"");
+
+        assert!(subject.is_err());
+    }
+
+    #[test]
+    fn encoded_file_round_trip() {
+        let file = File {
+            path: "foo.txt".into(),
+            content: FileContent::Encoded {
+                content: TextData {
+                    data: "Hello!\r\n你好! 🌍".into(),
+                    encoding: Encoding::Utf16Le,
+                    line_ending: LineEnding::CrLf,
+                },
+            },
+        };
+        let json = serde_json::to_string(&file).expect("Should pass");
+
+        assert_eq!(
+            json,
+            r#"{"path":"foo.txt","content":{"Encoded":{"content":{"data":"Hello!\r\n你好! 🌍","encoding":"UTF-16-LE","line_ending":"CrLf"}}}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<File>(&json).expect("Should pass"),
+            file
+        );
+    }
+
+    #[test]
+    fn binary_file_round_trip() {
+        let file = File {
+            path: "foo.bin".into(),
+            content: FileContent::Binary {
+                content: vec![1, 2, 3, 0, 4, 5, 0],
+            },
+        };
+        let json = serde_json::to_string(&file).expect("Should pass");
+
+        assert_eq!(
+            json,
+            r#"{"path":"foo.bin","content":{"Binary":{"content":"AQIDAAQFAA=="}}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<File>(&json).expect("Should pass"),
+            file
+        );
+    }
+}
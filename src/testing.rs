@@ -0,0 +1,55 @@
+//! Assertions for tests that work with encoded content. Only intended for use in tests.
+
+use crate::TextData;
+
+/// Asserts that both byte slices decode to the same text, ignoring differences in their encodings
+/// and BOMs. E.g. the UTF-8, UTF-8-BOM and UTF-16 encodings of the same text are all the same.
+///
+/// Panics if the text differs, or if either of them can't be decoded.
+#[track_caller]
+pub fn assert_same_text(a: &[u8], b: &[u8]) {
+    let a = TextData::try_from(a).unwrap_or_else(|e| panic!("Left bytes can't be decoded: {e}"));
+    let b = TextData::try_from(b).unwrap_or_else(|e| panic!("Right bytes can't be decoded: {e}"));
+
+    assert_eq!(
+        a.data, b.data,
+        "Text differs between {} and {}",
+        a.encoding, b.encoding
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::testing::assert_same_text;
+
+    const UTF8_CONTENT: &[u8] = b"Hello! \xE4\xBD\xA0\xE5\xA5\xBD! \xF0\x9F\x8C\x8D";
+    const UTF8BOM_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF8BOM/unicode"
+    ));
+    const UTF16LE_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF16LE/unicode"
+    ));
+
+    #[test_case(UTF8_CONTENT, UTF8BOM_CONTENT; "UTF-8 and UTF-8-BOM")]
+    #[test_case(UTF8_CONTENT, UTF16LE_CONTENT; "UTF-8 and UTF-16LE")]
+    #[test_case(UTF8BOM_CONTENT, UTF16LE_CONTENT; "UTF-8-BOM and UTF-16LE")]
+    fn same_text(a: &[u8], b: &[u8]) {
+        assert_same_text(a, b);
+    }
+
+    #[test]
+    #[should_panic(expected = "Text differs between UTF-8 and UTF-16-LE")]
+    fn different_text() {
+        assert_same_text(b"Hello?", UTF16LE_CONTENT);
+    }
+
+    #[test]
+    #[should_panic(expected = "Right bytes can't be decoded: File content is binary")]
+    fn binary() {
+        assert_same_text(UTF8_CONTENT, b"\x12\x34\0");
+    }
+}
@@ -1,46 +1,307 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ffi::OsString;
 use std::fs;
-use std::io::Read;
+use std::ops::Range;
 use std::path::Path;
+use std::str::CharIndices;
 
-use crate::constants::{
-    BINARY_DETECTION_THRESHOLD, UTF16BE_BOM, UTF16LE_BOM, UTF16_BOM_LENGTH, UTF8_BOM,
-    UTF8_BOM_LENGTH, ZERO_BYTE,
+use crate::binary::{
+    detect_bomless_utf16, detect_periodic_utf16, is_binary, BinaryHeuristic, BinaryVerdict,
 };
+use crate::constants::{BINARY_DETECTION_THRESHOLD, MAX_DECODE_INPUT_BYTES};
+#[cfg(feature = "encoding_rs")]
+use crate::encoding::other_encoding;
 use crate::encoding::Encoding;
+use crate::file::io_error_at;
+use crate::line_ending::LineEnding;
+use crate::script::Script;
 use crate::utf16::{to_u16_be, to_u16_le, UnevenByteSequenceError};
+use crate::utf32::{to_u32_be, to_u32_le, MisalignedByteSequenceError};
+use crate::utf7::{self, InvalidUtf7Error};
 use crate::FileError;
+use crate::{cp037, windows1252};
 
-/// A struct to hold the data of a text file and the encoding used to read it.
-#[derive(Debug, PartialEq)]
+/// A struct to hold the data of a text file, the encoding used to read it, and the style of line
+/// breaks found in it.
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextData {
     pub data: String,
     pub encoding: Encoding,
+    pub line_ending: LineEnding,
+}
+
+/// UTF-8 content borrowed from the bytes it was read from, rather than copied into a [TextData].
+/// See [TextData::borrow_utf8].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BorrowedTextData<'a> {
+    pub data: &'a str,
+    pub encoding: Encoding,
 }
 
 /// The possible errors that can occur when working with [TextData] structs.
 #[derive(Debug, thiserror::Error)]
 pub enum TextDataError {
+    #[error("Invalid UTF-8 sequence at byte offset {offset}")]
+    FromUtf8 {
+        offset: usize,
+        #[source]
+        source: std::str::Utf8Error,
+    },
+
+    #[error("Invalid UTF-16 sequence at byte offset {offset}")]
+    FromUtf16 {
+        offset: usize,
+        #[source]
+        source: std::char::DecodeUtf16Error,
+    },
+
     #[error(transparent)]
-    FromUtf8(#[from] std::string::FromUtf8Error),
+    UnevenByteSequence(#[from] UnevenByteSequenceError),
 
     #[error(transparent)]
-    FromUtf16(#[from] std::string::FromUtf16Error),
+    MisalignedByteSequence(#[from] MisalignedByteSequenceError),
 
     #[error(transparent)]
-    UnevenByteSequence(#[from] UnevenByteSequenceError),
+    FromUtf7(#[from] InvalidUtf7Error),
+
+    #[error("Invalid UTF-32 scalar value {0:#X}")]
+    InvalidScalarValue(u32),
 
     #[error("File content is binary")]
     Binary,
+
+    #[error("{0} content can't be borrowed, it must be decoded into a TextData")]
+    NotBorrowable(Encoding),
+
+    /// The content is too large to be decoded into a [String] on this target, as described for
+    /// [TextData::try_from_bytes_with_options]
+    #[error(
+        "Content is {size} bytes, which is over the limit of {limit} bytes that can be decoded"
+    )]
+    TooLarge { size: usize, limit: usize },
+
+    #[error("Unicode noncharacter U+{:04X} at byte offset {offset}", *character as u32)]
+    Noncharacter { offset: usize, character: char },
+
+    #[error("Content starts with more than one BOM: {0} followed by {1}")]
+    MultipleBoms(Encoding, Encoding),
+
+    /// The content is only the start of the BOM of the `expected` encoding, e.g. from a truncated
+    /// download
+    #[error("Content is a truncated {expected} BOM")]
+    TruncatedBom { expected: Encoding },
+
+    #[cfg(feature = "encoding_rs")]
+    #[error("Invalid {0} sequence")]
+    FromOther(&'static str),
+
+    #[cfg(feature = "encoding_rs")]
+    #[error("Unsupported encoding label {0:?}")]
+    UnsupportedLabel(String),
+}
+
+impl TextDataError {
+    /// The byte offset of the first invalid sequence in the input, for UTF-8, UTF-16 and UTF-7 decoding errors
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            TextDataError::FromUtf8 { offset, .. }
+            | TextDataError::FromUtf16 { offset, .. }
+            | TextDataError::Noncharacter { offset, .. } => Some(*offset),
+            TextDataError::FromUtf7(e) => Some(e.offset),
+            _ => None,
+        }
+    }
+
+    /// Moves the offset of the error along by `by` bytes, to account for a stripped BOM
+    pub(crate) fn offset_by(self, by: usize) -> Self {
+        match self {
+            TextDataError::FromUtf8 { offset, source } => TextDataError::FromUtf8 {
+                offset: offset + by,
+                source,
+            },
+            TextDataError::FromUtf16 { offset, source } => TextDataError::FromUtf16 {
+                offset: offset + by,
+                source,
+            },
+            TextDataError::Noncharacter { offset, character } => TextDataError::Noncharacter {
+                offset: offset + by,
+                character,
+            },
+            TextDataError::FromUtf7(e) => TextDataError::FromUtf7(InvalidUtf7Error {
+                offset: e.offset + by,
+            }),
+            error => error,
+        }
+    }
+}
+
+impl From<std::str::Utf8Error> for TextDataError {
+    fn from(source: std::str::Utf8Error) -> Self {
+        TextDataError::FromUtf8 {
+            offset: source.valid_up_to(),
+            source,
+        }
+    }
+}
+
+impl From<std::string::FromUtf8Error> for TextDataError {
+    fn from(error: std::string::FromUtf8Error) -> Self {
+        error.utf8_error().into()
+    }
+}
+
+/// How strictly UTF-16 content is checked when decoding, see [TextDataOptions::utf16_strictness].
+/// Only UTF-16 content is checked, noncharacters are always accepted in UTF-8 and UTF-32 content.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Utf16Strictness {
+    /// Reject Unicode noncharacters (U+FDD0 to U+FDEF, and the last two code points of each plane,
+    /// e.g. U+FFFE and U+FFFF) in UTF-16 content with [TextDataError::Noncharacter]
+    Strict,
+    /// Accept noncharacters in UTF-16 content, as they are valid scalar values. Only malformed
+    /// surrogates are rejected.
+    #[default]
+    AllowNoncharacters,
+}
+
+/// Options that control how bytes are interpreted when building a [TextData].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TextDataOptions {
+    /// How many leading bytes to scan when deciding if content is binary.
+    /// `None` scans the entire input. Defaults to 8 thousand bytes (same as Git).
+    pub binary_scan_limit: Option<usize>,
+    /// How to decide if the scanned bytes are binary. Defaults to [BinaryHeuristic::NullByte].
+    pub binary_heuristic: BinaryHeuristic,
+    /// Whether to decode content without a BOM as UTF-16 if it looks like UTF-16. Off by default,
+    /// as UTF-16 content contains zero-bytes and would otherwise be classed as binary.
+    ///
+    /// Content looks like UTF-16 if more than 30% of the scanned bytes are zero-bytes, and at
+    /// least 90% of them are at odd offsets (UTF-16LE) or even offsets (UTF-16BE).
+    /// The detected [Encoding] has a BOM, so the content is written with one.
+    pub allow_bomless_utf16: bool,
+    /// Whether to keep the U+FEFF at the start of UTF-16 content as part of the decoded content,
+    /// rather than stripping it as a BOM. Off by default.
+    ///
+    /// A leading U+FEFF is almost always a BOM, but it's also a valid (if deprecated) zero width
+    /// no-break space, so a file that starts with one as content loses it when it's stripped.
+    /// The encoding is still detected from it, so the content is written with a BOM in front of
+    /// the preserved U+FEFF unless it's written without one, e.g. with [crate::WriteOptions::bom].
+    pub preserve_leading_feff: bool,
+    /// Whether to strip a BOM found straight after the first one, rather than failing with
+    /// [TextDataError::MultipleBoms]. Off by default, so corrupt content isn't passed on silently.
+    ///
+    /// Buggy tooling can add a UTF-8 BOM in front of content that already has one. The content is
+    /// decoded with the [Encoding] of the last BOM, as that is the one it was written in. Only a
+    /// UTF-8 BOM can be followed by a BOM of another encoding, as the bytes of those are valid
+    /// characters in the UTF-16 and UTF-32 encodings.
+    pub strip_repeated_boms: bool,
+    /// Whether to strip every UTF-8 BOM at the start of the content, as some tools add another
+    /// one each time they save, rather than failing with [TextDataError::MultipleBoms]. The
+    /// content is decoded as [Encoding::Utf8Bom]. Off by default.
+    ///
+    /// Unlike [TextDataOptions::strip_repeated_boms], a BOM of another encoding after a UTF-8 BOM
+    /// is still an error.
+    pub collapse_repeated_utf8_bom: bool,
+    /// Whether to replace unpaired surrogates in UTF-16 content with U+FFFD (the replacement
+    /// character), rather than failing with [TextDataError::FromUtf16]. Off by default.
+    ///
+    /// Lone surrogates come from UTF-16 strings that were split or joined in the middle of a
+    /// surrogate pair. The rest of the content is still decoded strictly, and
+    /// [TextData::try_from_bytes_with_report] returns how many surrogates were replaced.
+    pub replace_unpaired_surrogates: bool,
+    /// Whether to drop the last byte of UTF-16 content with an odd number of bytes, rather than
+    /// failing with [TextDataError::UnevenByteSequence]. Off by default.
+    ///
+    /// Some tools pad UTF-16 files with a single trailing byte. The content before it is decoded
+    /// as usual, and [TextData::try_from_bytes_with_report] returns whether a byte was dropped.
+    pub truncate_trailing_odd_byte: bool,
+    /// Whether Unicode noncharacters are accepted in UTF-16 content. Defaults to
+    /// [Utf16Strictness::AllowNoncharacters], which is how all content is decoded otherwise.
+    /// This has no effect on content in other encodings, e.g. UTF-8 and UTF-32.
+    ///
+    /// Noncharacters are reserved for internal use, e.g. as sentinels in data formats, so
+    /// [Utf16Strictness::Strict] can be used to catch them in content that is exchanged.
+    pub utf16_strictness: Utf16Strictness,
+}
+
+/// What was done to content to decode it as configured by the [TextDataOptions], as returned by
+/// [TextData::try_from_bytes_with_report]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct DecodeReport {
+    /// The number of unpaired surrogates replaced with U+FFFD, see
+    /// [TextDataOptions::replace_unpaired_surrogates]
+    pub replaced_surrogates: usize,
+    /// Whether a trailing odd byte was dropped, see [TextDataOptions::truncate_trailing_odd_byte]
+    pub truncated_odd_byte: bool,
+}
+
+/// A repair made by [TextData::try_from_bytes_repair] to content whose BOM didn't match the
+/// encoding of the rest of it
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BomRepair {
+    /// The encoding of the BOM the content started with
+    pub bom: Encoding,
+    /// The encoding the content after the BOM was decoded as instead, which it is also written in
+    pub decoded_as: Encoding,
+}
+
+impl Default for TextDataOptions {
+    fn default() -> Self {
+        TextDataOptions {
+            binary_scan_limit: Some(BINARY_DETECTION_THRESHOLD),
+            binary_heuristic: BinaryHeuristic::NullByte,
+            allow_bomless_utf16: false,
+            preserve_leading_feff: false,
+            strip_repeated_boms: false,
+            collapse_repeated_utf8_bom: false,
+            replace_unpaired_surrogates: false,
+            truncate_trailing_odd_byte: false,
+            utf16_strictness: Utf16Strictness::AllowNoncharacters,
+        }
+    }
+}
+
+impl TextDataOptions {
+    /// Check whether the bytes would be treated as binary with these options, and whether all of
+    /// them were scanned to decide. As only the first [TextDataOptions::binary_scan_limit] bytes
+    /// are scanned, content can be decoded as text even if it has binary data after them.
+    ///
+    /// This only applies the [TextDataOptions::binary_heuristic], so it doesn't account for a BOM
+    /// or BOM-less UTF-16 being detected, which skip the binary check when decoding.
+    pub fn binary_verdict(&self, bytes: &[u8]) -> BinaryVerdict {
+        self.binary_heuristic.verdict(bytes, self.binary_scan_limit)
+    }
+}
+
+impl From<String> for TextData {
+    fn from(data: String) -> Self {
+        TextData::from_string(data)
+    }
+}
+
+impl From<&str> for TextData {
+    fn from(data: &str) -> Self {
+        TextData::from_string(data.into())
+    }
+}
+
+impl TryFrom<Vec<u8>> for TextData {
+    type Error = TextDataError;
+
+    /// Decode the bytes, reusing their allocation for the decoded content when they are UTF-8
+    /// without a BOM, which avoids copying them.
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        TextData::try_from_vec_with_options(bytes, TextDataOptions::default()).map_err(|(e, _)| e)
+    }
 }
 
 impl TryFrom<&Path> for TextData {
     type Error = FileError;
 
     fn try_from(path: &Path) -> Result<Self, Self::Error> {
-        let mut file = fs::File::open(path)?;
-        let mut bytes: Vec<u8> = vec![];
-        file.read_to_end(&mut bytes)?;
-        Ok(TextData::try_from(bytes.as_slice())?)
+        let bytes = fs::read(path).map_err(io_error_at(path))?;
+        Ok(TextData::try_from(bytes)?)
     }
 }
 
@@ -48,47 +309,921 @@ impl TryFrom<&[u8]> for TextData {
     type Error = TextDataError;
 
     fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.starts_with(UTF8_BOM) {
-            Ok(TextData {
-                data: String::from_utf8(bytes[UTF8_BOM_LENGTH..].to_vec())?,
-                encoding: Encoding::Utf8Bom,
-            })
-        } else if bytes.starts_with(UTF16BE_BOM) {
-            Ok(TextData {
-                data: String::from_utf16(&to_u16_be(&bytes[UTF16_BOM_LENGTH..])?)?,
-                encoding: Encoding::Utf16Be,
-            })
-        } else if bytes.starts_with(UTF16LE_BOM) {
-            Ok(TextData {
-                data: String::from_utf16(&to_u16_le(&bytes[UTF16_BOM_LENGTH..])?)?,
-                encoding: Encoding::Utf16Le,
-            })
-        } else if is_binary(bytes) {
+        TextData::try_from_bytes_with_options(bytes, TextDataOptions::default())
+    }
+}
+
+impl TextData {
+    /// Builds a [TextData] from decoded content, detecting its [LineEnding]
+    pub(crate) fn new(data: String, encoding: Encoding) -> Self {
+        let line_ending = LineEnding::detect(&data);
+        TextData {
+            data,
+            encoding,
+            line_ending,
+        }
+    }
+
+    /// Create a [TextData] holding the given string, to be written as [Encoding::Utf8]
+    pub fn from_string(data: String) -> Self {
+        TextData::new(data, Encoding::Utf8)
+    }
+
+    /// Change the encoding the content will be written with
+    pub fn with_encoding(self, encoding: Encoding) -> Self {
+        TextData { encoding, ..self }
+    }
+
+    /// Decode the given bytes, detecting the encoding from the BOM, using the given [TextDataOptions].
+    ///
+    /// Content is decoded into a [String], which can hold at most `isize::MAX` bytes, and each byte
+    /// can take up to 3 bytes once decoded, so content over a third of that fails with
+    /// [TextDataError::TooLarge] rather than failing to allocate. That's about 715 MB on 32-bit
+    /// targets, and more than fits in memory on 64-bit ones.
+    pub fn try_from_bytes_with_options(
+        bytes: &[u8],
+        options: TextDataOptions,
+    ) -> Result<Self, TextDataError> {
+        TextData::try_from_bytes_with_report(bytes, options).map(|(content, _)| content)
+    }
+
+    /// Decode the given bytes in the same way as [TextData::try_from_bytes_with_options], returning
+    /// a [DecodeReport] of what had to be done to the content to decode it, e.g. how many unpaired
+    /// surrogates were replaced, along with the content.
+    pub fn try_from_bytes_with_report(
+        bytes: &[u8],
+        options: TextDataOptions,
+    ) -> Result<(Self, DecodeReport), TextDataError> {
+        let (encoding, bom_length) = detect_encoding(bytes, options)?;
+        let (data, report) = decode_with_options(&bytes[bom_length..], encoding, options)
+            .map_err(|e| e.offset_by(bom_length))?;
+        Ok((TextData::new(data, encoding), report))
+    }
+
+    /// Decode the given bytes, detecting the encoding from the BOM, without stripping the BOM, so the
+    /// decoded content starts with it as a U+FEFF, e.g. for tools that need to see exactly what is in
+    /// the file. This works for every encoding, unlike [TextDataOptions::preserve_leading_feff].
+    ///
+    /// The [Encoding] still has a BOM, so the content is written with one in front of the kept
+    /// U+FEFF, unless it's written without one, e.g. with [crate::WriteOptions::bom].
+    pub fn try_from_bytes_raw(bytes: &[u8]) -> Result<Self, TextDataError> {
+        let (encoding, _) = detect_encoding(bytes, TextDataOptions::default())?;
+        Ok(TextData::new(decode(bytes, encoding)?, encoding))
+    }
+
+    /// Decode the given bytes, detecting the encoding from the BOM, into their [char]s rather than a
+    /// [String], along with the detected [Encoding]. This saves finding the [char] boundaries in
+    /// the decoded content again when it's going to be worked on a [char] at a time.
+    pub fn try_chars_from_bytes(bytes: &[u8]) -> Result<(Vec<char>, Encoding), TextDataError> {
+        let (encoding, bom_length) = detect_encoding(bytes, TextDataOptions::default())?;
+        let chars =
+            decode_chars(&bytes[bom_length..], encoding).map_err(|e| e.offset_by(bom_length))?;
+        Ok((chars, encoding))
+    }
+
+    /// Borrow the given bytes as a [str] without copying them, if they are UTF-8 without a BOM.
+    ///
+    /// Content with a BOM needs to have it stripped, and other encodings need to be decoded, so
+    /// [TextDataError::NotBorrowable] is returned for them, and they should be decoded with
+    /// [TextData::try_from] instead. Binary content is rejected with [TextDataError::Binary].
+    pub fn borrow_utf8(bytes: &[u8]) -> Result<BorrowedTextData<'_>, TextDataError> {
+        match detect_encoding(bytes, TextDataOptions::default())? {
+            (Encoding::Utf8, 0) => Ok(BorrowedTextData {
+                data: std::str::from_utf8(bytes)?,
+                encoding: Encoding::Utf8,
+            }),
+            (encoding, _) => Err(TextDataError::NotBorrowable(encoding)),
+        }
+    }
+
+    /// Decode the given bytes in the same way as [TextData::try_from_bytes_with_options], reusing
+    /// their allocation for the decoded content when they are UTF-8 without a BOM.
+    /// The bytes are handed back along with the error if they can't be decoded.
+    pub(crate) fn try_from_vec_with_options(
+        bytes: Vec<u8>,
+        options: TextDataOptions,
+    ) -> Result<Self, (TextDataError, Vec<u8>)> {
+        match detect_encoding(&bytes, options) {
+            Ok((Encoding::Utf8, 0)) => match String::from_utf8(bytes) {
+                Ok(data) => Ok(TextData::new(data, Encoding::Utf8)),
+                Err(e) => Err((e.utf8_error().into(), e.into_bytes())),
+            },
+            Ok((encoding, bom_length)) => {
+                match decode_with_options(&bytes[bom_length..], encoding, options) {
+                    Ok((data, _)) => Ok(TextData::new(data, encoding)),
+                    Err(e) => Err((e.offset_by(bom_length), bytes)),
+                }
+            }
+            Err(e) => Err((e, bytes)),
+        }
+    }
+
+    /// Decode the given bytes, detecting the encoding from the BOM, replacing any invalid sequences
+    /// with U+FFFD (the replacement character) rather than failing.
+    ///
+    /// Binary content is still rejected with [TextDataError::Binary], which is the only error
+    /// that can be returned, other than [TextDataError::TooLarge] for content that is too large to
+    /// be decoded at all.
+    pub fn try_from_bytes_lossy(bytes: &[u8]) -> Result<Self, TextDataError> {
+        TextData::try_from_bytes_lossy_with(bytes, char::REPLACEMENT_CHARACTER)
+    }
+
+    /// Decode the given bytes in the same way as [TextData::try_from_bytes_lossy], replacing any
+    /// invalid sequences with the given [char] instead of U+FFFD, e.g. `?` when the content is
+    /// going somewhere that only supports ASCII. Each invalid sequence is replaced with a single
+    /// [char], in the same way as [String::from_utf8_lossy].
+    pub fn try_from_bytes_lossy_with(
+        bytes: &[u8],
+        replacement: char,
+    ) -> Result<Self, TextDataError> {
+        check_decode_size(bytes, MAX_DECODE_INPUT_BYTES)?;
+        if let Some((encoding, bom_length)) = Encoding::from_bom(bytes) {
+            Ok(TextData::new(
+                decode_lossy(&bytes[bom_length..], encoding, replacement),
+                encoding,
+            ))
+        } else if is_binary(bytes, Some(BINARY_DETECTION_THRESHOLD))
+            || detect_periodic_utf16(bytes, Some(BINARY_DETECTION_THRESHOLD)).is_some()
+        {
             Err(TextDataError::Binary)
         } else {
-            Ok(TextData {
-                data: String::from_utf8(bytes.to_vec())?,
-                encoding: Encoding::Utf8,
+            Ok(TextData::new(
+                from_utf8_lossy(bytes, replacement),
+                Encoding::Utf8,
+            ))
+        }
+    }
+
+    /// Decode the given bytes, detecting the encoding from the BOM, returning the longest prefix of
+    /// the content that could be decoded along with the error that stopped decoding, if any.
+    ///
+    /// Binary content has no valid prefix, so an empty string is returned with [TextDataError::Binary].
+    /// If there are several problems with the content, the error is for the earliest of them.
+    pub fn try_from_bytes_partial(bytes: &[u8]) -> (String, Option<TextDataError>) {
+        let (encoding, bom_length) = match detect_encoding(bytes, TextDataOptions::default()) {
+            Ok(detected) => detected,
+            Err(e) => return (String::new(), Some(e)),
+        };
+
+        let body = &bytes[bom_length..];
+        let mut end = body.len();
+        let mut error: Option<TextDataError> = None;
+        loop {
+            match decode(&body[..end], encoding) {
+                Ok(data) => return (data, error.map(|e| e.offset_by(bom_length))),
+                Err(e) => {
+                    end = valid_length(&body[..end], encoding, &e);
+                    error = Some(e);
+                }
+            }
+        }
+    }
+
+    /// Decode the given bytes as the given [Encoding], without detecting the encoding, e.g. when it
+    /// is already known from a Content-Type charset. The BOM of the encoding is stripped if the
+    /// bytes start with it, but no other BOM is looked for, and there is no check for binary
+    /// content. [Encoding::Utf8] has no BOM, so a leading UTF-8 BOM is kept as a U+FEFF.
+    pub fn try_from_bytes_as(bytes: &[u8], encoding: Encoding) -> Result<Self, TextDataError> {
+        let bom_length = encoding
+            .bom()
+            .filter(|bom| bytes.starts_with(bom))
+            .map_or(0, <[u8]>::len);
+        let data = decode(&bytes[bom_length..], encoding).map_err(|e| e.offset_by(bom_length))?;
+        Ok(TextData::new(data, encoding))
+    }
+
+    /// Decode the given bytes as the given [Encoding] if they start with the `signature`, e.g. the
+    /// magic number of a proprietary format, returning the stripped signature along with the
+    /// content so it can be written back before it. The rest of the bytes are decoded as with
+    /// [TextData::try_from_bytes_as].
+    ///
+    /// Bytes without the signature are decoded as with [TextData::try_from], detecting the
+    /// encoding from the BOM, and `None` is returned for the signature.
+    pub fn try_from_bytes_with_signature<'a>(
+        bytes: &'a [u8],
+        signature: &[u8],
+        encoding: Encoding,
+    ) -> Result<(Self, Option<&'a [u8]>), TextDataError> {
+        match bytes.strip_prefix(signature) {
+            Some(body) if !signature.is_empty() => {
+                let content = TextData::try_from_bytes_as(body, encoding)
+                    .map_err(|e| e.offset_by(signature.len()))?;
+                Ok((content, Some(&bytes[..signature.len()])))
+            }
+            _ => Ok((TextData::try_from(bytes)?, None)),
+        }
+    }
+
+    /// Decode the given bytes, e.g. a line from a list of file names, into an [OsString] that can be
+    /// used as a path. Bytes that decode as with [TextData::try_from] are converted from the decoded
+    /// content, and anything else is kept as losslessly as the platform allows:
+    ///
+    /// * On Unix, the raw bytes are wrapped as they are, as paths can be any bytes.
+    /// * On Windows, the bytes are read as little-endian UTF-16 code units, skipping a UTF-16LE
+    ///   BOM, so unpaired surrogates are kept. An odd number of bytes is decoded lossily as UTF-8.
+    /// * Elsewhere, the bytes are decoded lossily as UTF-8.
+    pub fn try_as_os_string(bytes: &[u8]) -> OsString {
+        if let Ok(content) = TextData::try_from(bytes) {
+            return content.data.into();
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            std::ffi::OsStr::from_bytes(bytes).to_os_string()
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::ffi::OsStringExt;
+            let units = bytes
+                .strip_prefix(Encoding::Utf16Le.bom().unwrap_or_default())
+                .unwrap_or(bytes);
+            match to_u16_le(units) {
+                Ok(units) => OsString::from_wide(&units),
+                Err(_) => String::from_utf8_lossy(bytes).into_owned().into(),
+            }
+        }
+        #[cfg(not(any(unix, windows)))]
+        {
+            String::from_utf8_lossy(bytes).into_owned().into()
+        }
+    }
+
+    /// Decode the given bytes, detecting the encoding from the BOM, and decoding them with the
+    /// `fallback` encoding if there is no BOM and they are not valid UTF-8.
+    ///
+    /// Encodings without a BOM, like [Encoding::Windows1252], are never detected automatically, as
+    /// most byte sequences are valid in them. This lets the caller opt-in to using one.
+    ///
+    /// UTF-7 content is always valid UTF-8, so with an [Encoding::Utf7] fallback, content without
+    /// a BOM that is 7-bit ASCII is decoded as UTF-7 instead, unless it isn't valid UTF-7.
+    pub fn try_from_bytes_with_fallback(
+        bytes: &[u8],
+        fallback: Encoding,
+    ) -> Result<Self, TextDataError> {
+        match TextData::try_from(bytes) {
+            Ok(content)
+                if fallback == Encoding::Utf7
+                    && content.encoding == Encoding::Utf8
+                    && bytes.is_ascii() =>
+            {
+                match utf7::decode(bytes) {
+                    Ok(data) => Ok(TextData::new(data, Encoding::Utf7)),
+                    Err(_) => Ok(content),
+                }
+            }
+            Err(TextDataError::FromUtf8 { .. }) if Encoding::from_bom(bytes).is_none() => {
+                Ok(TextData::new(decode(bytes, fallback)?, fallback))
+            }
+            result => result,
+        }
+    }
+
+    /// Decode the given bytes, salvaging content whose BOM doesn't match the encoding of the rest
+    /// of it, e.g. UTF-16 written after a UTF-8 BOM by a buggy exporter. This is a best-effort
+    /// heuristic, so only use it for content that is known to be damaged in this way.
+    ///
+    /// The bytes are first decoded as usual. If there is a BOM and the content after it can't be
+    /// decoded in the encoding of the BOM, or it decodes to text with a NUL in it (as UTF-16 does
+    /// when read as UTF-8), the content after the BOM is decoded in each of the other Unicode
+    /// encodings in turn, and the first one that decodes to text without a NUL is used. UTF-8 is
+    /// tried first, then UTF-16 in the byte order its zero-bytes suggest, then UTF-32. The
+    /// [BomRepair] that was applied is returned along with the content, or `None` if the bytes
+    /// decoded as usual. If no other encoding works, the result of decoding as usual is returned.
+    pub fn try_from_bytes_repair(bytes: &[u8]) -> Result<(Self, Option<BomRepair>), TextDataError> {
+        let result = TextData::try_from(bytes);
+        let Some((bom, bom_length)) = Encoding::from_bom(bytes) else {
+            return result.map(|content| (content, None));
+        };
+        if matches!(&result, Ok(content) if !content.data.contains('\0')) {
+            return result.map(|content| (content, None));
+        }
+
+        let body = &bytes[bom_length..];
+        let (first_utf16, second_utf16) = match detect_bomless_utf16(body, None) {
+            Some(Encoding::Utf16Be) => (Encoding::Utf16Be, Encoding::Utf16Le),
+            _ => (Encoding::Utf16Le, Encoding::Utf16Be),
+        };
+        let candidates = [
+            Encoding::Utf8Bom,
+            first_utf16,
+            second_utf16,
+            Encoding::Utf32Le,
+            Encoding::Utf32Be,
+        ];
+
+        candidates
+            .into_iter()
+            .filter(|encoding| *encoding != bom)
+            .find_map(|encoding| {
+                let data = decode(body, encoding)
+                    .ok()
+                    .filter(|data| !data.contains('\0'))?;
+                let repair = BomRepair {
+                    bom,
+                    decoded_as: encoding,
+                };
+                Some((TextData::new(data, encoding), Some(repair)))
+            })
+            .map_or_else(|| result.map(|content| (content, None)), Ok)
+    }
+
+    /// Decode the given bytes in the encoding with the given WHATWG label (e.g. "gb18030" or
+    /// "shift_jis"), using `encoding_rs`. As with the other ways of decoding, a BOM at the start
+    /// of the bytes takes precedence, and the encoding is detected from it instead.
+    ///
+    /// [TextDataError::UnsupportedLabel] is returned if `encoding_rs` doesn't recognise the label.
+    #[cfg(feature = "encoding_rs")]
+    pub fn try_from_bytes_with_label(bytes: &[u8], label: &str) -> Result<Self, TextDataError> {
+        if Encoding::from_bom(bytes).is_some() {
+            return TextData::try_from(bytes);
+        }
+
+        let encoding = Encoding::from_label(label)
+            .ok_or_else(|| TextDataError::UnsupportedLabel(label.into()))?;
+        Ok(TextData::new(decode(bytes, encoding)?, encoding))
+    }
+
+    /// Rewrite every line break in the content to use the given [LineEnding].
+    ///
+    /// [LineEnding::Mixed] and [LineEnding::None] aren't styles that can be written, so the content
+    /// is left untouched for them.
+    pub fn normalize_line_endings(&mut self, line_ending: LineEnding) {
+        if let Cow::Owned(data) = line_ending.convert(&self.data) {
+            self.data = data;
+            self.line_ending = LineEnding::detect(&self.data);
+        }
+    }
+
+    /// Add a line break to the end of the content if `present` is true and it doesn't end with one,
+    /// or remove the one it ends with if `present` is false. Only one line break is ever added or
+    /// removed. The line break added is `\r\n` if the [LineEnding] is [LineEnding::CrLf], and `\n`
+    /// otherwise.
+    pub fn ensure_trailing_newline(&mut self, present: bool) {
+        match (present, self.data.ends_with('\n')) {
+            (true, false) if self.line_ending == LineEnding::CrLf => self.data.push_str("\r\n"),
+            (true, false) => self.data.push('\n'),
+            (false, true) => {
+                self.data.pop();
+                if self.data.ends_with('\r') {
+                    self.data.pop();
+                }
+            }
+            _ => return,
+        }
+        self.line_ending = LineEnding::detect(&self.data);
+    }
+
+    /// Remove every U+FEFF (zero width no-break space) from the content, returning how many were
+    /// removed. This is the decoded form of a BOM that isn't at the start of the content, e.g. from
+    /// files that have been concatenated. The leading BOM is already removed when decoding.
+    pub fn strip_interior_boms(&mut self) -> usize {
+        let count = self.data.matches('\u{FEFF}').count();
+        if count > 0 {
+            self.data = self.data.replace('\u{FEFF}', "");
+        }
+
+        count
+    }
+
+    /// An iterator over the lines of the content, split on both `\n` and `\r\n`, with the line
+    /// breaks removed. This has the same semantics as [str::lines]: a final line break doesn't
+    /// produce an extra empty line, and a lone `\r` isn't treated as a line break.
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.data.lines()
+    }
+
+    /// The number of lines in the content, as yielded by [TextData::lines]
+    pub fn line_count(&self) -> usize {
+        self.lines().count()
+    }
+
+    /// Split the content at its first empty line, as in email headers or front matter, returning
+    /// the lines before it as the header and the content after it as the body. Lines are split in
+    /// the same way as [TextData::lines], so empty lines can end in `\n` or `\r\n`.
+    ///
+    /// The header doesn't include the line break before the empty line, and is empty if the
+    /// content starts with an empty line. If there is no empty line, the header is `None` and all
+    /// of the content is the body.
+    pub fn split_header(&self) -> (Option<&str>, &str) {
+        let mut header_end = 0;
+        let mut start = 0;
+        for line in self.data.split_inclusive('\n') {
+            let content = line
+                .strip_suffix('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line));
+            match content {
+                Some("") => {
+                    return (
+                        Some(&self.data[..header_end]),
+                        &self.data[start + line.len()..],
+                    )
+                }
+                Some(content) => header_end = start + content.len(),
+                None => break,
+            }
+            start += line.len();
+        }
+
+        (None, &self.data)
+    }
+
+    /// The BOM bytes that were stripped from the start of the content when it was decoded, and that
+    /// are written before it, based on its encoding. `None` for encodings without a BOM.
+    pub fn bom_bytes(&self) -> Option<&'static [u8]> {
+        self.encoding.bom()
+    }
+
+    /// The encoding that was used to decode the content, and that will be used to write it
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Consume the [TextData], returning the decoded content without copying it
+    pub fn into_string(self) -> String {
+        self.data
+    }
+
+    /// Compare the decoded content of two [TextData]s, ignoring their encodings and line endings.
+    ///
+    /// Unlike `==`, this is true for the same text decoded from different encodings, e.g. a UTF-8
+    /// file and a UTF-16 file with the same content.
+    pub fn content_eq(&self, other: &TextData) -> bool {
+        self.data == other.data
+    }
+
+    /// Compare two [TextData]s in the same way as `==`, except that [Encoding::Utf8] and
+    /// [Encoding::Utf8Bom] are treated as the same encoding, as they only differ in whether a BOM
+    /// is written. Every other encoding is only the same as itself, so e.g. [Encoding::Utf16Le]
+    /// and [Encoding::Utf16Be] are still different.
+    ///
+    /// This sits between `==` and [TextData::content_eq], which ignores the encodings entirely.
+    pub fn eq_ignore_bom(&self, other: &TextData) -> bool {
+        let same_encoding = match (self.encoding, other.encoding) {
+            (Encoding::Utf8 | Encoding::Utf8Bom, Encoding::Utf8 | Encoding::Utf8Bom) => true,
+            (a, b) => a == b,
+        };
+
+        same_encoding && self.data == other.data
+    }
+
+    /// The decoded content in a form for diffing, with every `\r\n` line break converted to `\n`
+    /// and without a leading U+FEFF, e.g. one kept by [TextDataOptions::preserve_leading_feff].
+    /// Two files that only differ in their encoding or line endings give the same content.
+    ///
+    /// As with [LineEnding], a lone `\r` isn't a line break, so it's left as it is.
+    pub fn diffable(&self) -> String {
+        let data = self.data.strip_prefix('\u{FEFF}').unwrap_or(&self.data);
+        data.replace("\r\n", "\n")
+    }
+
+    /// The decoded content in the given byte range, or `None` if either end of the range isn't on a
+    /// [char] boundary or is out of bounds, rather than panicking like indexing into the content.
+    pub fn slice(&self, range: Range<usize>) -> Option<&str> {
+        self.data.get(range)
+    }
+
+    /// The index of the [char] the first match of `needle` in the decoded content starts at, or
+    /// `None` if there isn't one. The index is in [char]s rather than bytes, so it's the same
+    /// whatever encoding the content was decoded from. When `case_insensitive` is set, ASCII
+    /// letters match regardless of case, while other characters still have to match exactly.
+    pub fn find(&self, needle: &str, case_insensitive: bool) -> Option<usize> {
+        let is_match = |start: usize| {
+            self.data
+                .get(start..start + needle.len())
+                .is_some_and(|candidate| match case_insensitive {
+                    true => candidate.eq_ignore_ascii_case(needle),
+                    false => candidate == needle,
+                })
+        };
+
+        self.data
+            .char_indices()
+            .map(|(start, _)| start)
+            .chain([self.data.len()])
+            .position(is_match)
+    }
+
+    /// An iterator over the [char]s of the decoded content and their byte offsets in it, as with
+    /// [str::char_indices]
+    pub fn char_indices(&self) -> CharIndices<'_> {
+        self.data.char_indices()
+    }
+
+    /// An iterator over the [char]s of the decoded content and the byte offsets they start at when
+    /// the content is written in its current encoding, including the BOM, e.g. to report positions
+    /// in the file on disk.
+    ///
+    /// For [Encoding::Utf7], where the bytes for a [char] depend on the ones around it, each [char]
+    /// is measured as if it were encoded on its own, so the offsets are only approximate.
+    pub fn encoded_char_offsets(&self) -> impl Iterator<Item = (usize, char)> + '_ {
+        let encoding = self.encoding;
+        let bom_length = encoding.bom().map_or(0, <[u8]>::len);
+
+        self.data.chars().scan(bom_length, move |offset, c| {
+            let start = *offset;
+            *offset += encoding.char_len(c);
+            Some((start, c))
+        })
+    }
+
+    /// A rough hint of the writing system the content is in: the [Script] most of its letters are
+    /// in, or `None` if it has no letters, e.g. if it's empty or only whitespace. Digits,
+    /// punctuation and symbols are shared between scripts, so they aren't counted.
+    pub fn dominant_script(&self) -> Option<Script> {
+        Script::dominant(&self.data)
+    }
+
+    /// Whether the decoded content is empty. Content that was only a BOM decodes to nothing, so it
+    /// is empty, even though its encoding still writes the BOM.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The number of Unicode scalar values ([char]s) in the content
+    pub fn char_count(&self) -> usize {
+        self.data.chars().count()
+    }
+
+    /// The number of bytes the content takes up when written in its current encoding, including
+    /// the BOM. This is the number of bytes [crate::FileContent::write] produces, without encoding
+    /// the content, except for [Encoding::Utf7] where it has to be encoded to be measured.
+    pub fn encoded_byte_count(&self) -> usize {
+        self.encoded_size_in(self.encoding)
+    }
+
+    /// The number of bytes the content would take up if it were written in the `target` encoding,
+    /// including its BOM, without encoding the content (except for [Encoding::Utf7]), e.g. to see
+    /// how much space converting the content would save. Characters that can't be represented in a
+    /// single-byte encoding are counted as one byte.
+    pub fn encoded_size_in(&self, target: Encoding) -> usize {
+        target.encoded_len(&self.data)
+    }
+
+    /// Whether every [char] in the content can be represented in the `target` encoding, so it can
+    /// be converted to it without losing anything. Any text can be written in the Unicode
+    /// encodings, so this is only false for single-byte encodings like [Encoding::Windows1252],
+    /// and encodings supported through `encoding_rs`.
+    pub fn can_encode_to(&self, target: Encoding) -> bool {
+        self.data.chars().all(|c| can_encode_char(c, target))
+    }
+
+    /// The distinct [char]s in the content that can't be represented in the `target` encoding, in
+    /// the order they first appear, e.g. to report what would be lost by converting the content to
+    /// a legacy encoding. This is empty whenever [TextData::can_encode_to] is true, so it's always
+    /// empty for the Unicode encodings.
+    pub fn lossy_chars_for(&self, target: Encoding) -> Vec<char> {
+        let mut seen = HashSet::new();
+        self.data
+            .chars()
+            .filter(|c| !can_encode_char(*c, target) && seen.insert(*c))
+            .collect()
+    }
+}
+
+/// Whether the [char] can be represented in the `target` encoding, as described for
+/// [TextData::can_encode_to]
+fn can_encode_char(c: char, target: Encoding) -> bool {
+    match target {
+        Encoding::Windows1252 => windows1252::from_char(c).is_some(),
+        Encoding::Cp037 => cp037::from_char(c).is_some(),
+        #[cfg(feature = "encoding_rs")]
+        Encoding::Other(_) => target.encode(c.encode_utf8(&mut [0; 4])).is_ok(),
+        _ => true,
+    }
+}
+
+/// Detects the encoding of the bytes, returning it along with the length of the BOM before the content.
+/// Only the first [TextDataOptions::binary_scan_limit] bytes are needed, so this can be run on the
+/// start of content that hasn't all been read yet, as [crate::IncrementalDecoder] does.
+pub(crate) fn detect_encoding(
+    bytes: &[u8],
+    options: TextDataOptions,
+) -> Result<(Encoding, usize), TextDataError> {
+    if let Some(expected) = Encoding::from_truncated_bom(bytes) {
+        return Err(TextDataError::TruncatedBom { expected });
+    }
+
+    if let Some((mut encoding, mut bom_length)) = Encoding::from_bom(bytes) {
+        if matches!(encoding, Encoding::Utf16Be | Encoding::Utf16Le)
+            && options.preserve_leading_feff
+        {
+            return Ok((encoding, 0));
+        }
+        while let Some((repeated, length)) = repeated_bom(&bytes[bom_length..], encoding) {
+            let collapse = options.collapse_repeated_utf8_bom
+                && (encoding, repeated) == (Encoding::Utf8Bom, Encoding::Utf8Bom);
+            if !(collapse || options.strip_repeated_boms) {
+                return Err(TextDataError::MultipleBoms(encoding, repeated));
+            }
+            encoding = repeated;
+            bom_length += length;
+        }
+        Ok((encoding, bom_length))
+    } else if let Some(encoding) = options
+        .allow_bomless_utf16
+        .then(|| detect_bomless_utf16(bytes, options.binary_scan_limit))
+        .flatten()
+    {
+        Ok((encoding, 0))
+    } else if options
+        .binary_heuristic
+        .is_binary(bytes, options.binary_scan_limit)
+    {
+        Err(TextDataError::Binary)
+    } else if !matches!(options.binary_heuristic, BinaryHeuristic::Custom(_))
+        && detect_periodic_utf16(bytes, options.binary_scan_limit).is_some()
+    {
+        // Strictly periodic zero-bytes aren't binary, but BOM-less UTF-16 is only decoded when it's
+        // allowed, and it isn't UTF-8 either
+        Err(TextDataError::Binary)
+    } else {
+        Ok((Encoding::Utf8, 0))
+    }
+}
+
+/// Returns the BOM at the start of the bytes that follow a BOM for the given [Encoding], if there is
+/// one, along with its length. After a UTF-16 or UTF-32 BOM only the same BOM is looked for.
+fn repeated_bom(bytes: &[u8], encoding: Encoding) -> Option<(Encoding, usize)> {
+    match encoding {
+        Encoding::Utf8Bom => Encoding::from_bom(bytes),
+        _ => encoding
+            .bom()
+            .filter(|bom| bytes.starts_with(bom))
+            .map(|bom| (encoding, bom.len())),
+    }
+}
+
+/// Checks that the bytes aren't over the `limit` of how many can be decoded
+fn check_decode_size(bytes: &[u8], limit: usize) -> Result<(), TextDataError> {
+    match bytes.len() {
+        size if size > limit => Err(TextDataError::TooLarge { size, limit }),
+        _ => Ok(()),
+    }
+}
+
+/// Decodes the given bytes (with any BOM already removed) as the given [Encoding]
+pub(crate) fn decode(bytes: &[u8], encoding: Encoding) -> Result<String, TextDataError> {
+    check_decode_size(bytes, MAX_DECODE_INPUT_BYTES)?;
+    match encoding {
+        Encoding::Utf8 | Encoding::Utf8Bom => Ok(String::from_utf8(bytes.to_vec())?),
+        Encoding::Utf16Be => from_utf16(&to_u16_be(bytes)?),
+        Encoding::Utf16Le => from_utf16(&to_u16_le(bytes)?),
+        Encoding::Utf32Be => from_utf32(&to_u32_be(bytes)?),
+        Encoding::Utf32Le => from_utf32(&to_u32_le(bytes)?),
+        Encoding::Utf7 => Ok(utf7::decode(bytes)?),
+        Encoding::Windows1252 => Ok(bytes.iter().copied().map(windows1252::to_char).collect()),
+        Encoding::Cp037 => Ok(bytes.iter().copied().map(cp037::to_char).collect()),
+        #[cfg(feature = "encoding_rs")]
+        Encoding::Other(name) => other_encoding(name)
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(String::from)
+            .ok_or(TextDataError::FromOther(name)),
+    }
+}
+
+/// Decodes the given bytes (with any BOM already removed) as the given [Encoding] straight into
+/// [char]s, rather than into a [String] as [decode] does
+fn decode_chars(bytes: &[u8], encoding: Encoding) -> Result<Vec<char>, TextDataError> {
+    check_decode_size(bytes, MAX_DECODE_INPUT_BYTES)?;
+    match encoding {
+        Encoding::Utf8 | Encoding::Utf8Bom => Ok(std::str::from_utf8(bytes)?.chars().collect()),
+        Encoding::Utf16Be => from_utf16(&to_u16_be(bytes)?),
+        Encoding::Utf16Le => from_utf16(&to_u16_le(bytes)?),
+        Encoding::Utf32Be => from_utf32(&to_u32_be(bytes)?),
+        Encoding::Utf32Le => from_utf32(&to_u32_le(bytes)?),
+        Encoding::Windows1252 => Ok(bytes.iter().copied().map(windows1252::to_char).collect()),
+        Encoding::Cp037 => Ok(bytes.iter().copied().map(cp037::to_char).collect()),
+        // These are only decoded into a String
+        encoding => Ok(decode(bytes, encoding)?.chars().collect()),
+    }
+}
+
+/// Decodes the given bytes (with any BOM already removed) as the given [Encoding], as configured by
+/// the [TextDataOptions], returning a [DecodeReport] along with the decoded content
+fn decode_with_options(
+    mut bytes: &[u8],
+    encoding: Encoding,
+    options: TextDataOptions,
+) -> Result<(String, DecodeReport), TextDataError> {
+    let mut report = DecodeReport::default();
+    let is_utf16 = matches!(encoding, Encoding::Utf16Be | Encoding::Utf16Le);
+    if is_utf16 && options.truncate_trailing_odd_byte && bytes.len() % 2 == 1 {
+        bytes = &bytes[..bytes.len() - 1];
+        report.truncated_odd_byte = true;
+    }
+
+    let units = match encoding {
+        Encoding::Utf16Be if options.replace_unpaired_surrogates => to_u16_be(bytes)?,
+        Encoding::Utf16Le if options.replace_unpaired_surrogates => to_u16_le(bytes)?,
+        _ => {
+            let data = decode(bytes, encoding)?;
+            check_utf16_strictness(&data, encoding, options)?;
+            return Ok((data, report));
+        }
+    };
+
+    let data: String = char::decode_utf16(units)
+        .map(|c| {
+            c.unwrap_or_else(|_| {
+                report.replaced_surrogates += 1;
+                char::REPLACEMENT_CHARACTER
             })
+        })
+        .collect();
+    check_utf16_strictness(&data, encoding, options)?;
+    Ok((data, report))
+}
+
+/// Checks content decoded from the given [Encoding] against the
+/// [TextDataOptions::utf16_strictness], failing with the byte offset of the first noncharacter in
+/// UTF-16 content if they aren't allowed
+pub(crate) fn check_utf16_strictness(
+    data: &str,
+    encoding: Encoding,
+    options: TextDataOptions,
+) -> Result<(), TextDataError> {
+    if !matches!(encoding, Encoding::Utf16Be | Encoding::Utf16Le)
+        || options.utf16_strictness == Utf16Strictness::AllowNoncharacters
+    {
+        return Ok(());
+    }
+
+    let mut offset = 0;
+    for character in data.chars() {
+        if is_noncharacter(character) {
+            return Err(TextDataError::Noncharacter { offset, character });
+        }
+        offset += character.len_utf16() * 2;
+    }
+
+    Ok(())
+}
+
+/// Whether the [char] is one of the 66 Unicode noncharacters
+fn is_noncharacter(c: char) -> bool {
+    matches!(c as u32, 0xFDD0..=0xFDEF) || c as u32 & 0xFFFE == 0xFFFE
+}
+
+/// Returns the length of the prefix of the bytes before the problem that caused the error when they
+/// were decoded as the given [Encoding]. The prefix may have problems of its own.
+fn valid_length(bytes: &[u8], encoding: Encoding, error: &TextDataError) -> usize {
+    match error {
+        TextDataError::UnevenByteSequence(_) => bytes.len() - 1,
+        TextDataError::MisalignedByteSequence(_) => bytes.len() - bytes.len() % 4,
+        TextDataError::InvalidScalarValue(_) => {
+            let units = match encoding {
+                Encoding::Utf32Be => to_u32_be(bytes),
+                _ => to_u32_le(bytes),
+            };
+            let valid_units = units
+                .unwrap_or_default()
+                .iter()
+                .take_while(|unit| char::from_u32(**unit).is_some())
+                .count();
+            valid_units * 4
+        }
+        error => error.offset().unwrap_or_default(),
+    }
+}
+
+/// Decodes the given bytes (with any BOM already removed) as the given [Encoding], replacing
+/// invalid sequences, and any incomplete trailing code unit, with the `replacement`
+fn decode_lossy(bytes: &[u8], encoding: Encoding, replacement: char) -> String {
+    let code_unit_size = match encoding {
+        Encoding::Utf8 | Encoding::Utf8Bom => return from_utf8_lossy(bytes, replacement),
+        Encoding::Utf7 => return utf7::decode_lossy(bytes, replacement),
+        Encoding::Windows1252 => return bytes.iter().copied().map(windows1252::to_char).collect(),
+        Encoding::Cp037 => return bytes.iter().copied().map(cp037::to_char).collect(),
+        #[cfg(feature = "encoding_rs")]
+        Encoding::Other(name) => return from_other_lossy(bytes, name, replacement),
+        Encoding::Utf16Be | Encoding::Utf16Le => 2,
+        Encoding::Utf32Be | Encoding::Utf32Le => 4,
+    };
+
+    // Truncated to a whole number of code units, so the conversions below can't fail
+    let (whole_units, remainder) = bytes.split_at(bytes.len() - bytes.len() % code_unit_size);
+    let mut data = match encoding {
+        Encoding::Utf16Be => {
+            from_utf16_lossy(&to_u16_be(whole_units).unwrap_or_default(), replacement)
+        }
+        Encoding::Utf16Le => {
+            from_utf16_lossy(&to_u16_le(whole_units).unwrap_or_default(), replacement)
+        }
+        Encoding::Utf32Be => {
+            from_utf32_lossy(&to_u32_be(whole_units).unwrap_or_default(), replacement)
+        }
+        _ => from_utf32_lossy(&to_u32_le(whole_units).unwrap_or_default(), replacement),
+    };
+
+    if !remainder.is_empty() {
+        data.push(replacement);
+    }
+
+    data
+}
+
+/// Builds a [String] from UTF-8 bytes, replacing each invalid sequence with the `replacement`
+fn from_utf8_lossy(mut bytes: &[u8], replacement: char) -> String {
+    let mut data = String::with_capacity(bytes.len());
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                data.push_str(valid);
+                return data;
+            }
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                data.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                data.push(replacement);
+                bytes = &rest[e.error_len().unwrap_or(rest.len())..];
+            }
+        }
+    }
+}
+
+/// Builds a [String] from UTF-16 code units, replacing any unpaired surrogate with the `replacement`
+fn from_utf16_lossy(units: &[u16], replacement: char) -> String {
+    char::decode_utf16(units.iter().copied())
+        .map(|c| c.unwrap_or(replacement))
+        .collect()
+}
+
+/// Builds a [String] from UTF-32 code units, replacing any unit that isn't a valid [char] with the
+/// `replacement`
+fn from_utf32_lossy(units: &[u32], replacement: char) -> String {
+    units
+        .iter()
+        .map(|unit| char::from_u32(*unit).unwrap_or(replacement))
+        .collect()
+}
+
+/// Decodes bytes in [Encoding::Other] with the given name, replacing each malformed sequence with
+/// the `replacement`
+#[cfg(feature = "encoding_rs")]
+fn from_other_lossy(mut bytes: &[u8], name: &str, replacement: char) -> String {
+    use encoding_rs::DecoderResult;
+
+    let mut decoder = other_encoding(name).new_decoder_without_bom_handling();
+    let mut data = String::new();
+    loop {
+        data.reserve(
+            decoder
+                .max_utf8_buffer_length_without_replacement(bytes.len())
+                .unwrap_or(bytes.len()),
+        );
+        let (result, read) = decoder.decode_to_string_without_replacement(bytes, &mut data, true);
+        bytes = &bytes[read..];
+        match result {
+            DecoderResult::InputEmpty => return data,
+            DecoderResult::OutputFull => {}
+            DecoderResult::Malformed(_, _) => data.push(replacement),
         }
     }
 }
 
-/// Returns true if it finds a zero-byte within the first 8 thousand bytes (same as Git)
-fn is_binary(bytes: &[u8]) -> bool {
-    bytes
+/// Builds a [String] (or any collection of [char]s) from UTF-16 code units, failing on the first
+/// unit that isn't part of a valid [char]. The error holds the byte offset of that unit
+fn from_utf16<T: FromIterator<char>>(units: &[u16]) -> Result<T, TextDataError> {
+    let mut offset = 0;
+    char::decode_utf16(units.iter().copied())
+        .map(|c| match c {
+            Ok(c) => {
+                offset += c.len_utf16() * 2;
+                Ok(c)
+            }
+            Err(source) => Err(TextDataError::FromUtf16 { offset, source }),
+        })
+        .collect()
+}
+
+/// Builds a [String] (or any collection of [char]s) from UTF-32 code units, failing on the first
+/// unit that isn't a valid [char]
+fn from_utf32<T: FromIterator<char>>(units: &[u32]) -> Result<T, TextDataError> {
+    units
         .iter()
-        .take(BINARY_DETECTION_THRESHOLD)
-        .any(|b| *b == ZERO_BYTE)
+        .map(|unit| char::from_u32(*unit).ok_or(TextDataError::InvalidScalarValue(*unit)))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::ops::Range;
+
     use test_case::test_case;
 
     use crate::{
-        encoding::Encoding,
-        text_data::{TextData, TextDataError},
+        binary::{BinaryHeuristic, BinaryVerdict},
+        constants::{UTF16BE_BOM, UTF16LE_BOM, UTF32BE_BOM, UTF32LE_BOM, UTF8_BOM},
+        encoding::{to_utf16_le, Encoding},
+        file::FileContent,
+        line_ending::LineEnding,
+        script::Script,
+        text_data::{
+            BomRepair, BorrowedTextData, DecodeReport, TextData, TextDataError, TextDataOptions,
+            Utf16Strictness,
+        },
     };
 
     const UTF8BOM_EMPTY_CONTENT: &[u8] = include_bytes!(concat!(
@@ -130,17 +1265,44 @@ mod tests {
         "/tests/data/UTF16LE/unicode"
     ));
 
-    #[test_case(""; "No content")]
-    #[test_case("Hello!"; "ASCII chars")]
-    #[test_case("Hello! 你好! 🌍"; "Unicode chars")]
-    fn from_valid_utf8(input: &str) {
-        let subject = TextData::try_from(input.as_bytes()).expect("Should pass");
-        let expected = TextData {
-            data: input.into(),
-            encoding: Encoding::Utf8,
-        };
-
-        assert_eq!(subject, expected);
+    const UTF32BE_EMPTY_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF32BE/empty"
+    ));
+    const UTF32BE_ASCII_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF32BE/ascii"
+    ));
+    const UTF32BE_UNICODE_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF32BE/unicode"
+    ));
+
+    const UTF32LE_EMPTY_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF32LE/empty"
+    ));
+    const UTF32LE_ASCII_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF32LE/ascii"
+    ));
+    const UTF32LE_UNICODE_CONTENT: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/UTF32LE/unicode"
+    ));
+
+    #[test_case(""; "No content")]
+    #[test_case("Hello!"; "ASCII chars")]
+    #[test_case("Hello! 你好! 🌍"; "Unicode chars")]
+    fn from_valid_utf8(input: &str) {
+        let subject = TextData::try_from(input.as_bytes()).expect("Should pass");
+        let expected = TextData {
+            data: input.into(),
+            encoding: Encoding::Utf8,
+            line_ending: LineEnding::None,
+        };
+
+        assert_eq!(subject, expected);
     }
 
     // Overlong encoding refers to using more bytes than necessary for a character [https://codedocs.org/what-is/utf-8#Overlong_encodings]
@@ -151,7 +1313,7 @@ mod tests {
     fn from_invalid_utf8(bytes: &[u8]) {
         let subject = TextData::try_from(bytes);
 
-        assert!(matches!(subject, Err(TextDataError::FromUtf8(_))))
+        assert!(matches!(subject, Err(TextDataError::FromUtf8 { .. })))
     }
 
     #[test_case(UTF8BOM_EMPTY_CONTENT, ""; "No content")]
@@ -162,6 +1324,7 @@ mod tests {
         let expected = TextData {
             data: content.into(),
             encoding: Encoding::Utf8Bom,
+            line_ending: LineEnding::None,
         };
 
         assert_eq!(subject, expected);
@@ -174,7 +1337,7 @@ mod tests {
     fn from_invalid_utf8_with_bom(bytes: &[u8]) {
         let subject = TextData::try_from(bytes);
 
-        assert!(matches!(subject, Err(TextDataError::FromUtf8(_))));
+        assert!(matches!(subject, Err(TextDataError::FromUtf8 { .. })));
     }
 
     #[test_case(UTF16BE_EMPTY_CONTENT, ""; "No content")]
@@ -185,6 +1348,7 @@ mod tests {
         let expected = TextData {
             data: content.into(),
             encoding: Encoding::Utf16Be,
+            line_ending: LineEnding::None,
         };
 
         assert_eq!(subject, expected);
@@ -197,7 +1361,7 @@ mod tests {
     fn from_invalid_utf16be(bytes: &[u8]) {
         let subject = TextData::try_from(bytes);
 
-        assert!(matches!(subject, Err(TextDataError::FromUtf16(_))));
+        assert!(matches!(subject, Err(TextDataError::FromUtf16 { .. })));
     }
 
     #[test_case(UTF16LE_EMPTY_CONTENT, ""; "No content")]
@@ -208,6 +1372,7 @@ mod tests {
         let expected = TextData {
             data: content.into(),
             encoding: Encoding::Utf16Le,
+            line_ending: LineEnding::None,
         };
 
         assert_eq!(subject, expected);
@@ -220,7 +1385,241 @@ mod tests {
     fn from_invalid_utf16le(bytes: &[u8]) {
         let subject = TextData::try_from(bytes);
 
-        assert!(matches!(subject, Err(TextDataError::FromUtf16(_))));
+        assert!(matches!(subject, Err(TextDataError::FromUtf16 { .. })));
+    }
+
+    #[test_case(UTF8BOM_UNICODE_CONTENT, Encoding::Utf8Bom; "UTF-8 with BOM")]
+    #[test_case(UTF16BE_UNICODE_CONTENT, Encoding::Utf16Be; "UTF-16BE")]
+    #[test_case(UTF16LE_UNICODE_CONTENT, Encoding::Utf16Le; "UTF-16LE")]
+    #[test_case(UTF32BE_UNICODE_CONTENT, Encoding::Utf32Be; "UTF-32BE")]
+    #[test_case(UTF32LE_UNICODE_CONTENT, Encoding::Utf32Le; "UTF-32LE")]
+    #[test_case(UTF16LE_EMPTY_CONTENT, Encoding::Utf16Le; "Only a BOM")]
+    fn from_bytes_raw(bytes: &[u8], encoding: Encoding) {
+        let subject = TextData::try_from_bytes_raw(bytes).expect("Should pass");
+        let stripped = TextData::try_from(bytes).expect("Should pass");
+
+        assert_eq!(subject.data.chars().next(), Some('\u{FEFF}'));
+        assert_eq!(
+            subject.data.strip_prefix('\u{FEFF}'),
+            Some(stripped.data.as_str())
+        );
+        assert_eq!(subject.encoding, encoding);
+    }
+
+    #[test]
+    fn from_bytes_raw_without_bom() {
+        let subject = TextData::try_from_bytes_raw(b"Hello!").expect("Should pass");
+
+        assert_eq!(subject, TextData::new("Hello!".into(), Encoding::Utf8));
+    }
+
+    #[test]
+    fn from_bytes_raw_invalid() {
+        let subject =
+            TextData::try_from_bytes_raw(b"\xEF\xBB\xBF\xC1\x80").expect_err("Should fail");
+
+        assert_eq!(subject.offset(), Some(3));
+    }
+
+    #[test_case(b"\xFE\xFF\xD8\xA5", "\u{FFFD}", 1; "UTF-16BE invalid high surrogate")]
+    #[test_case(b"\xFE\xFF\xDC\xA5", "\u{FFFD}", 1; "UTF-16BE invalid low surrogate")]
+    #[test_case(b"\xFF\xFE\xA5\xD8", "\u{FFFD}", 1; "UTF-16LE invalid high surrogate")]
+    #[test_case(b"\xFF\xFE\xA5\xDCH\x00\x3D\xD8", "\u{FFFD}H\u{FFFD}", 2; "UTF-16LE several surrogates")]
+    #[test_case(UTF16LE_UNICODE_CONTENT, "Hello! 你好! 🌍", 0; "Valid surrogate pair")]
+    fn from_utf16_replacing_unpaired_surrogates(bytes: &[u8], content: &str, replaced: usize) {
+        let options = TextDataOptions {
+            replace_unpaired_surrogates: true,
+            ..Default::default()
+        };
+        let (subject, report) =
+            TextData::try_from_bytes_with_report(bytes, options).expect("Should pass");
+
+        assert_eq!(subject.data, content);
+        assert_eq!(report.replaced_surrogates, replaced);
+    }
+
+    #[test_case(b"\xFF\xFEH\x00i\x00\x0A", false, Err(()); "Not truncated")]
+    #[test_case(b"\xFF\xFEH\x00i\x00\x0A", true, Ok("Hi"); "Truncated")]
+    #[test_case(b"\xFF\xFEH\x00i\x00", true, Ok("Hi"); "Even length")]
+    #[test_case(b"\xFE\xFF\x00H\x00i\x0A", true, Ok("Hi"); "Truncated UTF-16BE")]
+    fn from_utf16_with_trailing_odd_byte(
+        bytes: &[u8],
+        truncate_trailing_odd_byte: bool,
+        expected: Result<&str, ()>,
+    ) {
+        let options = TextDataOptions {
+            truncate_trailing_odd_byte,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_report(bytes, options);
+
+        match expected {
+            Ok(content) => {
+                let (subject, report) = subject.expect("Should pass");
+                assert_eq!(subject.data, content);
+                assert_eq!(report.truncated_odd_byte, bytes.len() % 2 == 1);
+            }
+            Err(()) => assert!(matches!(subject, Err(TextDataError::UnevenByteSequence(_)))),
+        }
+    }
+
+    #[test]
+    fn from_utf8_with_trailing_odd_byte() {
+        let options = TextDataOptions {
+            truncate_trailing_odd_byte: true,
+            ..Default::default()
+        };
+        let (subject, report) =
+            TextData::try_from_bytes_with_report(b"Hi!", options).expect("Should pass");
+
+        assert_eq!(subject.data, "Hi!");
+        assert_eq!(report, DecodeReport::default());
+    }
+
+    #[test_case(b"\xFE\xFF\x00H\xFF\xFE", Utf16Strictness::AllowNoncharacters, Ok("H\u{FFFE}"); "UTF-16BE U+FFFE allowed")]
+    #[test_case(b"\xFE\xFF\x00H\xFF\xFE", Utf16Strictness::Strict, Err(4); "UTF-16BE U+FFFE rejected")]
+    #[test_case(b"\xFF\xFE\xFE\xFFH\x00", Utf16Strictness::Strict, Err(2); "UTF-16LE U+FFFE rejected")]
+    #[test_case(b"\xFF\xFE\x3D\xD8\x0D\xDF\xD0\xFD", Utf16Strictness::Strict, Err(6); "Offset after a surrogate pair")]
+    #[test_case(b"\xFF\xFE\xFF\xDB\xFF\xDF", Utf16Strictness::Strict, Err(2); "U+10FFFF rejected")]
+    #[test_case(b"\xFF\xFE\xFD\xFFH\x00", Utf16Strictness::Strict, Ok("\u{FFFD}H"); "U+FFFD is a character")]
+    fn from_utf16_with_strictness(
+        bytes: &[u8],
+        utf16_strictness: Utf16Strictness,
+        expected: Result<&str, usize>,
+    ) {
+        let options = TextDataOptions {
+            utf16_strictness,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(bytes, options);
+
+        match expected {
+            Ok(content) => assert_eq!(subject.expect("Should pass").data, content),
+            Err(offset) => assert!(matches!(
+                subject,
+                Err(TextDataError::Noncharacter { offset: o, .. }) if o == offset
+            )),
+        }
+    }
+
+    #[test]
+    fn utf16_strictness_defaults_to_allow_noncharacters() {
+        assert_eq!(
+            TextDataOptions::default().utf16_strictness,
+            Utf16Strictness::AllowNoncharacters
+        );
+        assert_eq!(
+            TextData::try_from(b"\xFE\xFF\xFF\xFE".as_slice())
+                .expect("Should pass")
+                .data,
+            "\u{FFFE}"
+        );
+    }
+
+    #[test]
+    fn noncharacter_error_message() {
+        let options = TextDataOptions {
+            utf16_strictness: Utf16Strictness::Strict,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(b"\xFE\xFF\xFF\xFE", options)
+            .expect_err("Should fail");
+
+        assert_eq!(
+            subject.to_string(),
+            "Unicode noncharacter U+FFFE at byte offset 2"
+        );
+    }
+
+    #[test]
+    fn from_utf8_with_strict_utf16() {
+        let options = TextDataOptions {
+            utf16_strictness: Utf16Strictness::Strict,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options("\u{FFFE}".as_bytes(), options)
+            .expect("Should pass");
+
+        assert_eq!(subject.data, "\u{FFFE}");
+    }
+
+    #[test]
+    fn from_uneven_utf16_replacing_unpaired_surrogates() {
+        let options = TextDataOptions {
+            replace_unpaired_surrogates: true,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(b"\xFF\xFE\xA5\xDCH", options);
+
+        assert!(matches!(subject, Err(TextDataError::UnevenByteSequence(_))));
+    }
+
+    #[test_case(UTF32BE_EMPTY_CONTENT, ""; "No content")]
+    #[test_case(UTF32BE_ASCII_CONTENT, "Hello!"; "ASCII chars")]
+    #[test_case(UTF32BE_UNICODE_CONTENT, "Hello! 你好! 🌍"; "Unicode chars")]
+    fn from_valid_utf32be(bytes: &[u8], content: &str) {
+        let subject = TextData::try_from(bytes).expect("Should pass");
+        let expected = TextData {
+            data: content.into(),
+            encoding: Encoding::Utf32Be,
+            line_ending: LineEnding::None,
+        };
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case(b"\x00\x00\xFE\xFF\x00\x00\xD8\x3D", 0xD83D; "Surrogate code point")]
+    #[test_case(b"\x00\x00\xFE\xFF\x00\x11\x00\x00", 0x110000; "Code points above maximum")]
+    fn from_invalid_utf32be(bytes: &[u8], expected: u32) {
+        let subject = TextData::try_from(bytes);
+
+        assert!(
+            matches!(subject, Err(TextDataError::InvalidScalarValue(value)) if value == expected)
+        );
+    }
+
+    #[test]
+    fn from_incomplete_utf32be() {
+        let subject = TextData::try_from(b"\x00\x00\xFE\xFF\x00\x00\x00".as_slice());
+
+        assert!(matches!(
+            subject,
+            Err(TextDataError::MisalignedByteSequence(_))
+        ));
+    }
+
+    #[test_case(UTF32LE_EMPTY_CONTENT, ""; "No content")]
+    #[test_case(UTF32LE_ASCII_CONTENT, "Hello!"; "ASCII chars")]
+    #[test_case(UTF32LE_UNICODE_CONTENT, "Hello! 你好! 🌍"; "Unicode chars")]
+    fn from_valid_utf32le(bytes: &[u8], content: &str) {
+        let subject = TextData::try_from(bytes).expect("Should pass");
+        let expected = TextData {
+            data: content.into(),
+            encoding: Encoding::Utf32Le,
+            line_ending: LineEnding::None,
+        };
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case(b"\xFF\xFE\x00\x00\x3D\xD8\x00\x00", 0xD83D; "Surrogate code point")]
+    #[test_case(b"\xFF\xFE\x00\x00\x00\x00\x11\x00", 0x110000; "Code points above maximum")]
+    fn from_invalid_utf32le(bytes: &[u8], expected: u32) {
+        let subject = TextData::try_from(bytes);
+
+        assert!(
+            matches!(subject, Err(TextDataError::InvalidScalarValue(value)) if value == expected)
+        );
+    }
+
+    #[test]
+    fn from_incomplete_utf32le() {
+        let subject = TextData::try_from(b"\xFF\xFE\x00\x00\x48\x00".as_slice());
+
+        assert!(matches!(
+            subject,
+            Err(TextDataError::MisalignedByteSequence(_))
+        ));
     }
 
     #[test_case(b"\0"; "Single zero-byte")]
@@ -231,4 +1630,1074 @@ mod tests {
 
         assert!(matches!(subject, Err(TextDataError::Binary)));
     }
+
+    #[test_case(b"\xEF", Encoding::Utf8Bom; "One byte of a UTF-8 BOM")]
+    #[test_case(b"\xEF\xBB", Encoding::Utf8Bom; "Two bytes of a UTF-8 BOM")]
+    #[test_case(b"\xFE", Encoding::Utf16Be; "One byte of a UTF-16BE BOM")]
+    #[test_case(b"\xFF", Encoding::Utf16Le; "One byte of a UTF-16LE BOM")]
+    #[test_case(b"\xFF\xFE\0", Encoding::Utf32Le; "Three bytes of a UTF-32LE BOM")]
+    #[test_case(b"\0\0\xFE", Encoding::Utf32Be; "Three bytes of a UTF-32BE BOM")]
+    fn from_truncated_bom(bytes: &[u8], expected: Encoding) {
+        let subject = TextData::try_from(bytes).expect_err("Should fail");
+
+        assert!(matches!(subject, TextDataError::TruncatedBom { expected: e } if e == expected));
+    }
+
+    #[test]
+    fn from_truncated_bom_with_content() {
+        let subject = TextData::try_from(b"\xEF\xBBHi".as_slice()).expect_err("Should fail");
+
+        assert!(matches!(subject, TextDataError::FromUtf8 { offset: 0, .. }));
+    }
+
+    #[test_case(Some(4), true; "Zero-byte at the boundary")]
+    #[test_case(Some(3), false; "Zero-byte just past the boundary")]
+    #[test_case(None, true; "Unbounded scan")]
+    fn binary_scan_limit(binary_scan_limit: Option<usize>, is_binary: bool) {
+        let bytes = b"\x12\x34\x56\0";
+        let options = TextDataOptions {
+            binary_scan_limit,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(bytes, options);
+
+        assert_eq!(matches!(subject, Err(TextDataError::Binary)), is_binary);
+    }
+
+    #[test_case(BinaryHeuristic::NullByte, false; "Null byte")]
+    #[test_case(BinaryHeuristic::ControlRatio { threshold: 0.1 }, true; "Control ratio")]
+    fn binary_heuristic(binary_heuristic: BinaryHeuristic, is_binary: bool) {
+        let bytes = [0x01; 64];
+        let options = TextDataOptions {
+            binary_heuristic,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(&bytes, options);
+
+        assert_eq!(matches!(subject, Err(TextDataError::Binary)), is_binary);
+    }
+
+    #[test]
+    fn binary_verdict_of_partial_scan() {
+        let mut bytes = vec![b'a'; 9 * 1024];
+        bytes.extend_from_slice(&[0; 1024]);
+        let options = TextDataOptions::default();
+
+        assert!(TextData::try_from_bytes_with_options(&bytes, options).is_ok());
+        assert_eq!(
+            options.binary_verdict(&bytes),
+            BinaryVerdict {
+                is_binary: false,
+                scanned_fully: false
+            }
+        );
+
+        let options = TextDataOptions {
+            binary_scan_limit: None,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.binary_verdict(&bytes),
+            BinaryVerdict {
+                is_binary: true,
+                scanned_fully: true
+            }
+        );
+    }
+
+    #[test_case(false, "A"; "BOM stripped")]
+    #[test_case(true, "\u{FEFF}A"; "BOM preserved")]
+    fn preserve_leading_feff(preserve_leading_feff: bool, expected: &str) {
+        let bytes = b"\xFF\xFE\x41\x00";
+        let options = TextDataOptions {
+            preserve_leading_feff,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(bytes, options).expect("Should pass");
+
+        assert_eq!(subject, TextData::new(expected.into(), Encoding::Utf16Le));
+    }
+
+    #[test]
+    fn preserve_leading_feff_round_trip() {
+        let bytes = b"\xFF\xFE\x41\x00";
+        let options = TextDataOptions {
+            preserve_leading_feff: true,
+            ..Default::default()
+        };
+        let content = FileContent::Encoded {
+            content: TextData::try_from_bytes_with_options(bytes, options).expect("Should pass"),
+        };
+        let mut written = vec![];
+        content
+            .write_with_bom(&mut written, false)
+            .expect("Should pass");
+
+        assert_eq!(written, bytes);
+    }
+
+    #[test_case(b"\xEF\xBB\xBF\xFF\xFEA\x00", Encoding::Utf8Bom, Encoding::Utf16Le; "UTF-8 BOM then UTF-16LE BOM")]
+    #[test_case(b"\xEF\xBB\xBF\xEF\xBB\xBFA", Encoding::Utf8Bom, Encoding::Utf8Bom; "UTF-8 BOM then UTF-8 BOM")]
+    #[test_case(b"\xFE\xFF\xFE\xFF\x00A", Encoding::Utf16Be, Encoding::Utf16Be; "UTF-16BE BOM then UTF-16BE BOM")]
+    fn multiple_boms(bytes: &[u8], first: Encoding, second: Encoding) {
+        let subject = TextData::try_from_bytes_with_options(bytes, TextDataOptions::default());
+
+        assert!(
+            matches!(subject, Err(TextDataError::MultipleBoms(a, b)) if a == first && b == second)
+        );
+    }
+
+    #[test_case(b"\xEF\xBB\xBF\xFF\xFEA\x00", Encoding::Utf16Le; "UTF-8 BOM then UTF-16LE BOM")]
+    #[test_case(b"\xEF\xBB\xBF\xEF\xBB\xBFA", Encoding::Utf8Bom; "UTF-8 BOM then UTF-8 BOM")]
+    #[test_case(b"\xEF\xBB\xBF\xEF\xBB\xBF\xFE\xFF\x00A", Encoding::Utf16Be; "Three BOMs")]
+    fn strip_repeated_boms(bytes: &[u8], encoding: Encoding) {
+        let options = TextDataOptions {
+            strip_repeated_boms: true,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(bytes, options).expect("Should pass");
+
+        assert_eq!(subject, TextData::new("A".into(), encoding));
+    }
+
+    #[test_case(b"\xEF\xBB\xBF\xEF\xBB\xBFA"; "Two UTF-8 BOMs")]
+    #[test_case(b"\xEF\xBB\xBF\xEF\xBB\xBF\xEF\xBB\xBFA"; "Three UTF-8 BOMs")]
+    fn collapse_repeated_utf8_bom(bytes: &[u8]) {
+        let options = TextDataOptions {
+            collapse_repeated_utf8_bom: true,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(bytes, options).expect("Should pass");
+
+        assert_eq!(subject, TextData::new("A".into(), Encoding::Utf8Bom));
+    }
+
+    #[test]
+    fn collapse_repeated_utf8_bom_before_other_bom() {
+        let bytes = b"\xEF\xBB\xBF\xEF\xBB\xBF\xFF\xFEA\x00";
+        let options = TextDataOptions {
+            collapse_repeated_utf8_bom: true,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(bytes, options);
+
+        assert!(matches!(
+            subject,
+            Err(TextDataError::MultipleBoms(
+                Encoding::Utf8Bom,
+                Encoding::Utf16Le
+            ))
+        ));
+    }
+
+    #[test_case(b"\xFF\xFE\xFE\xFFA\x00"; "UTF-16LE BOM then UTF-16BE BOM")]
+    #[test_case(b"\xFE\xFF\xEF\xBB"; "UTF-16BE BOM then UTF-8 BOM bytes")]
+    fn other_bom_after_utf16_bom_is_content(bytes: &[u8]) {
+        let subject = TextData::try_from_bytes_with_options(bytes, TextDataOptions::default());
+
+        assert!(!matches!(subject, Err(TextDataError::MultipleBoms(..))));
+    }
+
+    #[test]
+    fn default_binary_scan_limit() {
+        let mut bytes = vec![b'a'; 8_000];
+        bytes.push(0);
+
+        assert!(TextData::try_from(bytes.as_slice()).is_ok());
+        bytes.insert(0, 0);
+        assert!(matches!(
+            TextData::try_from(bytes.as_slice()),
+            Err(TextDataError::Binary)
+        ));
+    }
+
+    #[test_case(b"\xC1\x80", 2; "Overlong encoding")]
+    #[test_case(b"\x80\xA2", 2; "Invalid start byte")]
+    #[test_case(b"\xE0\xA4", 1; "Incomplete sequence")]
+    #[test_case(b"\xEF\xBB\xBF\xC1\x80", 2; "Overlong encoding with BOM")]
+    #[test_case(b"\xFE\xFF\xD8\xA5", 1; "Invalid UTF-16BE high surrogate")]
+    #[test_case(b"\xFF\xFE\xA5\xDC", 1; "Invalid UTF-16LE low surrogate")]
+    #[test_case(b"\xFF\xFE\x48\x00\x65", 1; "Uneven UTF-16LE byte sequence")]
+    #[test_case(b"\x00\x00\xFE\xFF\x00\x11\x00\x00", 1; "UTF-32BE code point above maximum")]
+    fn from_invalid_bytes_lossy(bytes: &[u8], replacement_count: usize) {
+        let subject = TextData::try_from_bytes_lossy(bytes).expect("Should pass");
+
+        assert_eq!(
+            subject.data.matches(char::REPLACEMENT_CHARACTER).count(),
+            replacement_count
+        );
+    }
+
+    #[test_case(b"\xC1\x80", 2; "Overlong encoding")]
+    #[test_case(b"\x80\xA2", 2; "Invalid start byte")]
+    #[test_case(b"\xE0\xA4", 1; "Incomplete sequence")]
+    #[test_case(b"\xEF\xBB\xBF\xC1\x80", 2; "Overlong encoding with BOM")]
+    #[test_case(b"\xFE\xFF\xD8\xA5", 1; "Invalid UTF-16BE high surrogate")]
+    #[test_case(b"\xFF\xFE\xA5\xDC", 1; "Invalid UTF-16LE low surrogate")]
+    #[test_case(b"\xFF\xFE\x48\x00\x65", 1; "Uneven UTF-16LE byte sequence")]
+    #[test_case(b"\x00\x00\xFE\xFF\x00\x11\x00\x00", 1; "UTF-32BE code point above maximum")]
+    fn from_invalid_bytes_lossy_with(bytes: &[u8], replacement_count: usize) {
+        let subject = TextData::try_from_bytes_lossy_with(bytes, '?').expect("Should pass");
+
+        assert_eq!(subject.data.matches('?').count(), replacement_count);
+        assert!(!subject.data.contains(char::REPLACEMENT_CHARACTER));
+    }
+
+    #[test_case(b"Caf\xE9 ok", "Caf? ok", Encoding::Utf8; "UTF-8")]
+    #[test_case(b"\xFF\xFEH\x00\x00\xDCi\x00", "H?i", Encoding::Utf16Le; "UTF-16LE")]
+    fn from_bytes_lossy_with(bytes: &[u8], content: &str, encoding: Encoding) {
+        let subject = TextData::try_from_bytes_lossy_with(bytes, '?').expect("Should pass");
+
+        assert_eq!(subject, TextData::new(content.into(), encoding));
+    }
+
+    #[test_case(UTF8BOM_UNICODE_CONTENT, Encoding::Utf8Bom; "UTF-8 with BOM")]
+    #[test_case(UTF16BE_UNICODE_CONTENT, Encoding::Utf16Be; "UTF-16BE")]
+    #[test_case(UTF16LE_UNICODE_CONTENT, Encoding::Utf16Le; "UTF-16LE")]
+    #[test_case(UTF32LE_UNICODE_CONTENT, Encoding::Utf32Le; "UTF-32LE")]
+    fn from_valid_bytes_lossy(bytes: &[u8], encoding: Encoding) {
+        let subject = TextData::try_from_bytes_lossy(bytes).expect("Should pass");
+        let expected = TextData {
+            data: "Hello! 你好! 🌍".into(),
+            encoding,
+            line_ending: LineEnding::None,
+        };
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test]
+    fn from_binary_lossy() {
+        let subject = TextData::try_from_bytes_lossy(b"\x12\x34\0");
+
+        assert!(matches!(subject, Err(TextDataError::Binary)));
+    }
+
+    #[test_case(b"Hello!", "Hello!", Encoding::Utf8; "Valid UTF-8 is not decoded with the fallback")]
+    #[test_case(b"Caf\xE9 \x80", "Café €", Encoding::Windows1252; "Invalid UTF-8")]
+    #[test_case(UTF16LE_ASCII_CONTENT, "Hello!", Encoding::Utf16Le; "BOM is detected")]
+    fn from_bytes_with_windows_1252_fallback(bytes: &[u8], content: &str, encoding: Encoding) {
+        let subject = TextData::try_from_bytes_with_fallback(bytes, Encoding::Windows1252)
+            .expect("Should pass");
+        let expected = TextData {
+            data: content.into(),
+            encoding,
+            line_ending: LineEnding::None,
+        };
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case(b"Caf\xE9\0"; "Binary")]
+    #[test_case(b"\xEF\xBB\xBFCaf\xE9"; "Invalid UTF-8 with BOM")]
+    fn from_bytes_with_windows_1252_fallback_not_used(bytes: &[u8]) {
+        let subject = TextData::try_from_bytes_with_fallback(bytes, Encoding::Windows1252);
+
+        assert!(subject.is_err());
+    }
+
+    #[test_case(b"\xC8\x85\x93\x93\x96", "Hello"; "ascii chars")]
+    #[test_case(b"\xC3\x81\x86\x51", "Café"; "latin-1 chars")]
+    fn from_bytes_with_cp037_fallback(bytes: &[u8], content: &str) {
+        let subject =
+            TextData::try_from_bytes_with_fallback(bytes, Encoding::Cp037).expect("Should pass");
+
+        assert_eq!(subject, TextData::new(content.into(), Encoding::Cp037));
+    }
+
+    #[test_case(&UTF16LE_ASCII_CONTENT[2..], "Hello!", Encoding::Utf16Le; "BOM-less UTF-16LE")]
+    #[test_case(UTF16LE_ASCII_CONTENT, "Hello!", Encoding::Utf16Le; "UTF-16LE BOM is stripped")]
+    #[test_case(b"\xFE\xFFH\x00", "\u{FFFE}H", Encoding::Utf16Le; "Other BOM is content")]
+    #[test_case(b"\xEF\xBB\xBFHi", "\u{FEFF}Hi", Encoding::Utf8; "UTF-8 BOM is content of UTF-8")]
+    #[test_case(b"\xEF\xBB\xBFHi", "Hi", Encoding::Utf8Bom; "UTF-8 BOM is stripped")]
+    #[test_case(b"H\0i\0\0", "H\0i\0\0", Encoding::Utf8; "Zero-bytes aren't binary")]
+    fn from_bytes_as(bytes: &[u8], content: &str, encoding: Encoding) {
+        let subject = TextData::try_from_bytes_as(bytes, encoding).expect("Should pass");
+
+        assert_eq!(subject, TextData::new(content.into(), encoding));
+    }
+
+    #[test_case(b"FCR\x01H\x00i\x00", Some(b"FCR\x01"), "Hi", Encoding::Utf16Le; "Signature")]
+    #[test_case(b"FCR\x01\xFF\xFEH\x00i\x00", Some(b"FCR\x01"), "Hi", Encoding::Utf16Le; "Signature before a BOM")]
+    #[test_case(b"\xFF\xFEH\x00i\x00", None, "Hi", Encoding::Utf16Le; "BOM without the signature")]
+    #[test_case(b"FCRHi", None, "FCRHi", Encoding::Utf8; "Partial signature")]
+    fn from_bytes_with_signature(
+        bytes: &[u8],
+        signature: Option<&[u8]>,
+        content: &str,
+        encoding: Encoding,
+    ) {
+        let subject = TextData::try_from_bytes_with_signature(bytes, b"FCR\x01", Encoding::Utf16Le)
+            .expect("Should pass");
+
+        assert_eq!(
+            subject,
+            (TextData::new(content.into(), encoding), signature)
+        );
+    }
+
+    #[test]
+    fn from_bytes_with_signature_invalid() {
+        let subject =
+            TextData::try_from_bytes_with_signature(b"SIG\0\xC1\x80", b"SIG\0", Encoding::Utf8)
+                .expect_err("Should fail");
+
+        assert_eq!(subject.offset(), Some(4));
+    }
+
+    #[test_case(b"report.txt", "report.txt"; "UTF-8")]
+    #[test_case(UTF16LE_UNICODE_CONTENT, "Hello! 你好! 🌍"; "UTF-16LE with a BOM")]
+    fn as_os_string(bytes: &[u8], expected: &str) {
+        assert_eq!(TextData::try_as_os_string(bytes), expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn as_os_string_invalid_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let bytes = b"caf\xE9.txt";
+        let subject = TextData::try_as_os_string(bytes);
+
+        assert_eq!(subject.as_bytes(), bytes);
+    }
+
+    #[test]
+    fn from_bytes_as_invalid() {
+        let subject = TextData::try_from_bytes_as(b"\xFF\xFEH\x00\x00\xDC", Encoding::Utf16Le)
+            .expect_err("Should fail");
+
+        assert_eq!(subject.offset(), Some(4));
+    }
+
+    #[test_case(b"Hi Mom -+Jjo--!", "Hi Mom -\u{263A}-!", Encoding::Utf7; "UTF-7")]
+    #[test_case(b"1 +- 1 = 2", "1 + 1 = 2", Encoding::Utf7; "UTF-7 with an escaped plus sign")]
+    #[test_case(b"1+1=2", "1+1=2", Encoding::Utf8; "Invalid UTF-7 is decoded as UTF-8")]
+    #[test_case(UTF16LE_ASCII_CONTENT, "Hello!", Encoding::Utf16Le; "BOM is detected")]
+    fn from_bytes_with_utf7_fallback(bytes: &[u8], content: &str, encoding: Encoding) {
+        let subject =
+            TextData::try_from_bytes_with_fallback(bytes, Encoding::Utf7).expect("Should pass");
+
+        assert_eq!(subject, TextData::new(content.into(), encoding));
+    }
+
+    #[test]
+    fn from_bytes_with_utf7_fallback_invalid() {
+        let subject = TextData::try_from_bytes_with_fallback(b"Caf\xE9", Encoding::Utf7)
+            .expect_err("Should fail");
+
+        assert_eq!(
+            subject.to_string(),
+            "Invalid UTF-7 sequence at byte offset 3"
+        );
+    }
+
+    #[test_case(b"\xEF\xBB\xBFH\x00i\x00!\x00", Encoding::Utf8Bom, Encoding::Utf16Le; "UTF-8 BOM with UTF-16LE")]
+    #[test_case(b"\xEF\xBB\xBF\x00H\x00i\x00!", Encoding::Utf8Bom, Encoding::Utf16Be; "UTF-8 BOM with UTF-16BE")]
+    #[test_case(b"\xFF\xFEHi!", Encoding::Utf16Le, Encoding::Utf8Bom; "UTF-16LE BOM with UTF-8")]
+    #[test_case(b"\xFE\xFFHi!", Encoding::Utf16Be, Encoding::Utf8Bom; "UTF-16BE BOM with UTF-8")]
+    #[test_case(b"\xFF\xFE\x00\x00H\x00i\x00!\x00", Encoding::Utf32Le, Encoding::Utf16Le; "UTF-32LE BOM with UTF-16LE")]
+    #[test_case(b"\xFF\xFEH\x00\x00\x00i\x00\x00\x00!\x00\x00\x00", Encoding::Utf16Le, Encoding::Utf32Le; "UTF-16LE BOM with UTF-32LE")]
+    fn from_bytes_repair(bytes: &[u8], bom: Encoding, decoded_as: Encoding) {
+        let (subject, repair) = TextData::try_from_bytes_repair(bytes).expect("Should pass");
+
+        assert_eq!(subject, TextData::new("Hi!".into(), decoded_as));
+        assert_eq!(repair, Some(BomRepair { bom, decoded_as }));
+    }
+
+    #[test_case(UTF16LE_UNICODE_CONTENT; "UTF-16LE")]
+    #[test_case(UTF8BOM_UNICODE_CONTENT; "UTF-8-BOM")]
+    #[test_case(b"Hello"; "No BOM")]
+    fn from_bytes_repair_not_needed(bytes: &[u8]) {
+        let (subject, repair) = TextData::try_from_bytes_repair(bytes).expect("Should pass");
+
+        assert_eq!(subject, TextData::try_from(bytes).expect("Should pass"));
+        assert_eq!(repair, None);
+    }
+
+    #[test_case(b"\xEF\xBB\xBF\xC1\x80\xC1"; "Invalid in every encoding")]
+    #[test_case(b"Hello\xC1\x80"; "Invalid without a BOM")]
+    fn from_bytes_repair_invalid(bytes: &[u8]) {
+        let subject = TextData::try_from_bytes_repair(bytes).expect_err("Should fail");
+
+        assert!(matches!(subject, TextDataError::FromUtf8 { .. }));
+    }
+
+    #[test_case(b"Hello\xC1\x80", "Invalid UTF-8 sequence at byte offset 5"; "Invalid UTF-8")]
+    #[test_case(b"\xEF\xBB\xBFHello\xC1\x80", "Invalid UTF-8 sequence at byte offset 8"; "Invalid UTF-8 with BOM")]
+    #[test_case(b"\xFE\xFF\x00\x48\xD8\x3D\xD8\x3D\xDE\x0A", "Invalid UTF-16 sequence at byte offset 4"; "Invalid UTF-16BE")]
+    #[test_case(b"\xFF\xFE\x3D\xD8\x0A\xDE\xA5\xDC", "Invalid UTF-16 sequence at byte offset 6"; "Invalid UTF-16LE after a surrogate pair")]
+    fn invalid_sequence_offset(bytes: &[u8], message: &str) {
+        let subject = TextData::try_from(bytes).expect_err("Should fail");
+
+        assert_eq!(subject.to_string(), message);
+    }
+
+    #[test_case(b"\xFE\xFF\x00", "UTF-16-BE body has odd length 1"; "BOM and a single byte")]
+    #[test_case(b"\xFF\xFE\x48\x00\x65", "UTF-16-LE body has odd length 3"; "Truncated UTF-16LE")]
+    fn uneven_utf16_length(bytes: &[u8], message: &str) {
+        let subject = TextData::try_from(bytes).expect_err("Should fail");
+
+        assert_eq!(subject.to_string(), message);
+    }
+
+    #[test_case(b"Hello!", "Hello!", None; "Valid UTF-8")]
+    #[test_case(b"Hello\xC1\x80 World!", "Hello", Some("Invalid UTF-8 sequence at byte offset 5"); "Invalid UTF-8")]
+    #[test_case(b"\xEF\xBB\xBFHello\xC1\x80", "Hello", Some("Invalid UTF-8 sequence at byte offset 8"); "Invalid UTF-8 with BOM")]
+    #[test_case(b"\xFE\xFF\x00\x48\xD8\x3D\xD8\x3D\xDE\x0A", "H", Some("Invalid UTF-16 sequence at byte offset 4"); "Invalid UTF-16BE")]
+    #[test_case(b"\xFF\xFE\x48\x00\x65", "H", Some("UTF-16-LE body has odd length 3"); "Uneven UTF-16LE")]
+    #[test_case(b"\xFF\xFE\x48\x00\x3D\xD8\x65", "H", Some("Invalid UTF-16 sequence at byte offset 4"); "Uneven and invalid UTF-16LE")]
+    #[test_case(b"\x00\x00\xFE\xFF\x00\x00\x00\x48\x00\x11\x00\x00", "H", Some("Invalid UTF-32 scalar value 0x110000"); "Invalid UTF-32BE")]
+    #[test_case(b"\xFF\xFE\x00\x00\x48\x00\x00\x00\x69", "H", Some("Byte sequence length is not a multiple of 4"); "Misaligned UTF-32LE")]
+    #[test_case(b"\x12\x34\0", "", Some("File content is binary"); "Binary")]
+    #[test_case(b"\xEF\xBB\xBF\xFF\xFEA\x00", "", Some("Content starts with more than one BOM: UTF-8-BOM followed by UTF-16-LE"); "Multiple BOMs")]
+    fn from_bytes_partial(bytes: &[u8], prefix: &str, message: Option<&str>) {
+        let (subject, error) = TextData::try_from_bytes_partial(bytes);
+
+        assert_eq!(subject, prefix);
+        assert_eq!(error.map(|e| e.to_string()).as_deref(), message);
+    }
+
+    #[test]
+    fn invalid_sequence_offset_accessor() {
+        let subject = TextData::try_from(b"\xFF\xFE\x48\x00\xA5\xDC".as_slice());
+
+        assert_eq!(subject.expect_err("Should fail").offset(), Some(4));
+        assert_eq!(TextDataError::Binary.offset(), None);
+    }
+
+    #[test_case(b"Hello!", LineEnding::None; "UTF-8 without line breaks")]
+    #[test_case(b"Hello!\nWorld!\n", LineEnding::Lf; "UTF-8 with LF")]
+    #[test_case(b"\xEF\xBB\xBFHello!\r\nWorld!", LineEnding::CrLf; "UTF-8 with BOM and CRLF")]
+    #[test_case(b"\xFF\xFE\x41\x00\x0D\x00\x0A\x00\x42\x00\x0A\x00", LineEnding::Mixed; "UTF-16LE with mixed line breaks")]
+    #[test_case(b"\xFE\xFF\x00\x41\x00\x0D\x00\x0A", LineEnding::CrLf; "UTF-16BE with CRLF")]
+    fn detect_line_ending(bytes: &[u8], line_ending: LineEnding) {
+        let subject = TextData::try_from(bytes).expect("Should pass");
+
+        assert_eq!(subject.line_ending, line_ending);
+    }
+
+    #[test_case("a\r\nb\nc", LineEnding::Lf, "a\nb\nc"; "Mixed to LF")]
+    #[test_case("a\r\nb\nc", LineEnding::CrLf, "a\r\nb\r\nc"; "Mixed to CRLF")]
+    #[test_case("a\r\nb\r\n", LineEnding::CrLf, "a\r\nb\r\n"; "CRLF to CRLF")]
+    #[test_case("a\r\nb\nc", LineEnding::Mixed, "a\r\nb\nc"; "Mixed is a no-op")]
+    #[test_case("a\r\nb\nc", LineEnding::None, "a\r\nb\nc"; "None is a no-op")]
+    fn normalize_line_endings(input: &str, line_ending: LineEnding, expected: &str) {
+        let mut subject = TextData::new(input.into(), Encoding::Utf16Le);
+        subject.normalize_line_endings(line_ending);
+
+        assert_eq!(subject, TextData::new(expected.into(), Encoding::Utf16Le));
+    }
+
+    #[test_case("", &[]; "No content")]
+    #[test_case("Hello!", &["Hello!"]; "No line breaks")]
+    #[test_case("Hello!\nWorld!", &["Hello!", "World!"]; "No trailing line break")]
+    #[test_case("Hello!\r\nWorld!\r\n", &["Hello!", "World!"]; "Trailing line break")]
+    #[test_case("Hello!\n\nWorld!\n\n", &["Hello!", "", "World!", ""]; "Empty lines")]
+    #[test_case("Hello!\rWorld!\r\n", &["Hello!\rWorld!"]; "Lone carriage return")]
+    fn lines(input: &str, expected: &[&str]) {
+        let subject = TextData::new(input.into(), Encoding::Utf8);
+
+        assert_eq!(subject.lines().collect::<Vec<_>>(), expected);
+        assert_eq!(subject.line_count(), expected.len());
+    }
+
+    #[test_case("---\ntitle: Hi\n---\n\nBody\n", Some("---\ntitle: Hi\n---"), "Body\n"; "Front matter")]
+    #[test_case("From: a\r\nTo: b\r\n\r\nBody\r\n\r\nMore", Some("From: a\r\nTo: b"), "Body\r\n\r\nMore"; "CRLF")]
+    #[test_case("\nBody", Some(""), "Body"; "Empty header")]
+    #[test_case("Header\n\n", Some("Header"), ""; "Empty body")]
+    #[test_case("Header\n\r\n", Some("Header"), ""; "Mixed line breaks")]
+    #[test_case("No\nheader\n", None, "No\nheader\n"; "No empty line")]
+    #[test_case("Lone\r\rcarriage return", None, "Lone\r\rcarriage return"; "Lone carriage return")]
+    #[test_case("", None, ""; "No content")]
+    fn split_header(input: &str, header: Option<&str>, body: &str) {
+        let subject = TextData::new(input.into(), Encoding::Utf8);
+
+        assert_eq!(subject.split_header(), (header, body));
+    }
+
+    #[test_case(UTF16LE_ASCII_CONTENT, Some(Script::Latin); "ASCII")]
+    #[test_case(UTF16LE_UNICODE_CONTENT, Some(Script::Latin); "Mostly ASCII")]
+    #[test_case("你好，世界！".as_bytes(), Some(Script::Cjk); "Chinese")]
+    #[test_case("Привет, мир!".as_bytes(), Some(Script::Cyrillic); "Cyrillic")]
+    #[test_case(UTF16LE_EMPTY_CONTENT, None; "No content")]
+    #[test_case(b" \r\n\t", None; "Whitespace")]
+    fn dominant_script(bytes: &[u8], expected: Option<Script>) {
+        let subject = TextData::try_from(bytes).expect("Should pass");
+
+        assert_eq!(subject.dominant_script(), expected);
+    }
+
+    #[test_case(UTF8BOM_EMPTY_CONTENT, true; "UTF-8-BOM only")]
+    #[test_case(UTF16BE_EMPTY_CONTENT, true; "UTF-16BE BOM only")]
+    #[test_case(UTF16LE_EMPTY_CONTENT, true; "UTF-16LE BOM only")]
+    #[test_case(UTF32BE_EMPTY_CONTENT, true; "UTF-32BE BOM only")]
+    #[test_case(UTF32LE_EMPTY_CONTENT, true; "UTF-32LE BOM only")]
+    #[test_case(b"", true; "No content")]
+    #[test_case(UTF16LE_ASCII_CONTENT, false; "ASCII chars")]
+    fn is_empty(bytes: &[u8], expected: bool) {
+        let subject = TextData::try_from(bytes).expect("Should pass");
+
+        assert_eq!(subject.is_empty(), expected);
+    }
+
+    #[test_case("", 0; "No content")]
+    #[test_case("Hello!", 6; "ASCII chars")]
+    #[test_case("éü你好", 4; "Basic Multilingual Plane chars")]
+    #[test_case("🌍🚀", 2; "Supplementary Multilingual Plane chars")]
+    fn char_count(input: &str, expected: usize) {
+        let subject = TextData::new(input.into(), Encoding::Utf16Le);
+
+        assert_eq!(subject.char_count(), expected);
+    }
+
+    #[test_case(""; "No content")]
+    #[test_case("Hello!"; "ASCII chars")]
+    #[test_case("éü你好"; "Basic Multilingual Plane chars")]
+    #[test_case("🌍🚀"; "Supplementary Multilingual Plane chars")]
+    #[test_case("Hello! 你好! 🌍"; "Mixed chars")]
+    fn encoded_byte_count(input: &str) {
+        for encoding in [
+            Encoding::Utf8,
+            Encoding::Utf8Bom,
+            Encoding::Utf16Be,
+            Encoding::Utf16Le,
+            Encoding::Utf32Be,
+            Encoding::Utf32Le,
+            Encoding::Utf7,
+        ] {
+            let subject = TextData::new(input.into(), encoding);
+            let encoded_byte_count = subject.encoded_byte_count();
+            let mut bytes = vec![];
+            FileContent::Encoded { content: subject }
+                .write(&mut bytes)
+                .expect("Should pass");
+
+            assert_eq!(encoded_byte_count, bytes.len(), "{encoding}");
+        }
+    }
+
+    #[test_case("", 0; "No content")]
+    #[test_case("Café €", 6; "Windows-1252 chars")]
+    fn encoded_byte_count_windows_1252(input: &str, expected: usize) {
+        let subject = TextData::new(input.into(), Encoding::Windows1252);
+
+        assert_eq!(subject.encoded_byte_count(), expected);
+    }
+
+    #[test_case(Encoding::Utf8, 6, 8; "UTF-8")]
+    #[test_case(Encoding::Utf8Bom, 9, 11; "UTF-8-BOM")]
+    #[test_case(Encoding::Utf16Be, 14, 10; "UTF-16BE")]
+    #[test_case(Encoding::Utf16Le, 14, 10; "UTF-16LE")]
+    #[test_case(Encoding::Utf32Be, 28, 12; "UTF-32BE")]
+    #[test_case(Encoding::Utf32Le, 28, 12; "UTF-32LE")]
+    #[test_case(Encoding::Windows1252, 6, 2; "Windows-1252")]
+    fn encoded_size_in(target: Encoding, ascii_size: usize, emoji_size: usize) {
+        let ascii = TextData::new("Hello!".into(), Encoding::Utf16Le);
+        let emoji = TextData::new("🌍🚀".into(), Encoding::Utf16Le);
+
+        assert_eq!(ascii.encoded_size_in(target), ascii_size);
+        assert_eq!(emoji.encoded_size_in(target), emoji_size);
+    }
+
+    #[test_case("Café €5", Encoding::Windows1252, true; "Windows-1252 chars")]
+    #[test_case("Café 🌍", Encoding::Windows1252, false; "Emoji in Windows-1252")]
+    #[test_case("Café ¢", Encoding::Cp037, true; "CP037 chars")]
+    #[test_case("€5", Encoding::Cp037, false; "Euro sign in CP037")]
+    #[test_case("Café 🌍", Encoding::Utf8, true; "Emoji in UTF-8")]
+    #[test_case("Café 🌍", Encoding::Utf16Be, true; "Emoji in UTF-16BE")]
+    #[test_case("Café 🌍", Encoding::Utf7, true; "Emoji in UTF-7")]
+    fn can_encode_to(data: &str, target: Encoding, expected: bool) {
+        let subject = TextData::new(data.into(), Encoding::Utf8);
+
+        assert_eq!(subject.can_encode_to(target), expected);
+        assert_eq!(target.encode(data).is_ok(), expected);
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test_case("こんにちは", true; "Japanese")]
+    #[test_case("こんにちは 🌍", false; "Emoji")]
+    fn can_encode_to_other(data: &str, expected: bool) {
+        let subject = TextData::new(data.into(), Encoding::Utf8);
+
+        assert_eq!(
+            subject.can_encode_to(Encoding::Other("Shift_JIS")),
+            expected
+        );
+    }
+
+    #[test_case("Café 🌍 and 🌍 🚀 €", Encoding::Windows1252, &['🌍', '🚀']; "Emoji in Windows-1252")]
+    #[test_case("Café 🌍 and 🌍 🚀 €", Encoding::Cp037, &['🌍', '🚀', '€']; "Emoji in IBM037")]
+    #[test_case("Café €", Encoding::Windows1252, &[]; "Nothing lost")]
+    #[test_case("Café 🌍 🚀", Encoding::Utf8, &[]; "Emoji in UTF-8")]
+    #[test_case("Café 🌍 🚀", Encoding::Utf16Le, &[]; "Emoji in UTF-16LE")]
+    fn lossy_chars_for(data: &str, target: Encoding, expected: &[char]) {
+        let subject = TextData::new(data.into(), Encoding::Utf8);
+
+        assert_eq!(subject.lossy_chars_for(target), expected);
+        assert_eq!(subject.can_encode_to(target), expected.is_empty());
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn lossy_chars_for_other() {
+        let subject = TextData::new("こんにちは 🌍🚀🌍".into(), Encoding::Utf8);
+
+        assert_eq!(
+            subject.lossy_chars_for(Encoding::Other("Shift_JIS")),
+            ['🌍', '🚀']
+        );
+    }
+
+    #[test_case(5, Ok(()); "At the limit")]
+    #[test_case(4, Err((5, 4)); "Over the limit")]
+    fn check_decode_size(limit: usize, expected: Result<(), (usize, usize)>) {
+        let subject = super::check_decode_size(b"Hello", limit);
+
+        match expected {
+            Ok(()) => assert!(subject.is_ok()),
+            Err((size, limit)) => assert!(matches!(
+                subject,
+                Err(TextDataError::TooLarge { size: s, limit: l }) if (s, l) == (size, limit)
+            )),
+        }
+    }
+
+    #[test]
+    fn too_large_error_message() {
+        let subject = TextDataError::TooLarge { size: 5, limit: 4 };
+
+        assert_eq!(
+            subject.to_string(),
+            "Content is 5 bytes, which is over the limit of 4 bytes that can be decoded"
+        );
+    }
+
+    #[test]
+    fn hash_set() {
+        let set: HashSet<TextData> = [
+            TextData::new("Hello!".into(), Encoding::Utf8),
+            TextData::new("Hello!".into(), Encoding::Utf8),
+            TextData::new("Hello!".into(), Encoding::Utf8Bom),
+            TextData::new("Hello!\n".into(), Encoding::Utf8),
+            TextData::try_from(UTF16LE_ASCII_CONTENT).expect("Should pass"),
+            TextData::try_from(UTF16LE_ASCII_CONTENT).expect("Should pass"),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(&TextData::new("Hello!".into(), Encoding::Utf16Le)));
+        assert!(!set.contains(&TextData::new("Hello!".into(), Encoding::Utf16Be)));
+    }
+
+    #[test]
+    fn group_by_encoding() {
+        let mut groups: HashMap<Encoding, usize> = HashMap::new();
+        for bytes in [
+            UTF8BOM_ASCII_CONTENT,
+            UTF16LE_ASCII_CONTENT,
+            UTF16LE_UNICODE_CONTENT,
+        ] {
+            let content = TextData::try_from(bytes).expect("Should pass");
+            *groups.entry(content.encoding).or_default() += 1;
+        }
+
+        assert_eq!(groups.get(&Encoding::Utf16Le), Some(&2));
+        assert_eq!(groups.get(&Encoding::Utf8Bom), Some(&1));
+    }
+
+    #[test_case(UTF8BOM_UNICODE_CONTENT, UTF16LE_UNICODE_CONTENT, true; "UTF-8 and UTF-16 with the same content")]
+    #[test_case(UTF16BE_UNICODE_CONTENT, UTF32LE_UNICODE_CONTENT, true; "UTF-16 and UTF-32 with the same content")]
+    #[test_case(UTF8BOM_UNICODE_CONTENT, UTF16LE_ASCII_CONTENT, false; "Different content")]
+    fn content_eq(a: &[u8], b: &[u8], expected: bool) {
+        let a = TextData::try_from(a).expect("Should pass");
+        let b = TextData::try_from(b).expect("Should pass");
+
+        assert_eq!(a.content_eq(&b), expected);
+        assert_ne!(a, b);
+    }
+
+    #[test_case(Encoding::Utf8, Encoding::Utf8Bom, true; "UTF-8 with and without a BOM")]
+    #[test_case(Encoding::Utf8Bom, Encoding::Utf8Bom, true; "Same encoding")]
+    #[test_case(Encoding::Utf16Le, Encoding::Utf16Be, false; "UTF-16 endianness")]
+    #[test_case(Encoding::Utf8, Encoding::Utf16Le, false; "Different encodings")]
+    fn eq_ignore_bom(a: Encoding, b: Encoding, expected: bool) {
+        let a = TextData::new("Hello! 你好! 🌍".into(), a);
+        let b = TextData::new("Hello! 你好! 🌍".into(), b);
+
+        assert_eq!(a.eq_ignore_bom(&b), expected);
+        assert_eq!(b.eq_ignore_bom(&a), expected);
+        assert!(a.content_eq(&b));
+    }
+
+    #[test]
+    fn eq_ignore_bom_different_content() {
+        let a = TextData::new("Hello!".into(), Encoding::Utf8);
+        let b = TextData::new("Hello".into(), Encoding::Utf8Bom);
+
+        assert!(!a.eq_ignore_bom(&b));
+    }
+
+    #[test_case("a\r\nb\r\n", "a\nb\n"; "CRLF")]
+    #[test_case("a\nb\r\n", "a\nb\n"; "Mixed")]
+    #[test_case("\u{FEFF}a\r\nb", "a\nb"; "Leading U+FEFF")]
+    #[test_case("a\rb\u{FEFF}", "a\rb\u{FEFF}"; "Lone CR and interior U+FEFF")]
+    fn diffable(input: &str, expected: &str) {
+        let subject = TextData::new(input.into(), Encoding::Utf16Le);
+
+        assert_eq!(subject.diffable(), expected);
+    }
+
+    #[test]
+    fn diffable_across_line_endings() {
+        let crlf = TextData::try_from(b"\xEF\xBB\xBFa\r\nb\r\n".as_slice()).expect("Should pass");
+        let lf = TextData::try_from(b"a\nb\n".as_slice()).expect("Should pass");
+
+        assert!(!crlf.content_eq(&lf));
+        assert_eq!(crlf.diffable(), lf.diffable());
+    }
+
+    #[test_case("a\nb", true, "a\nb\n", LineEnding::Lf; "Add LF")]
+    #[test_case("a\r\nb", true, "a\r\nb\r\n", LineEnding::CrLf; "Add CRLF")]
+    #[test_case("a", true, "a\n", LineEnding::Lf; "Add to a single line")]
+    #[test_case("a\n", true, "a\n", LineEnding::Lf; "Already present")]
+    #[test_case("a\nb\n\n", false, "a\nb\n", LineEnding::Lf; "Remove LF")]
+    #[test_case("a\r\nb\r\n", false, "a\r\nb", LineEnding::CrLf; "Remove CRLF")]
+    #[test_case("a\n", false, "a", LineEnding::None; "Remove the only line break")]
+    #[test_case("a\r", false, "a\r", LineEnding::None; "Already absent")]
+    fn ensure_trailing_newline(
+        input: &str,
+        present: bool,
+        expected: &str,
+        line_ending: LineEnding,
+    ) {
+        let mut subject = TextData::from(input);
+        subject.ensure_trailing_newline(present);
+
+        assert_eq!(subject.data, expected);
+        assert_eq!(subject.line_ending, line_ending);
+    }
+
+    #[test_case("Hello!", "Hello!", 0; "No BOMs")]
+    #[test_case("Hello!\u{FEFF} World!\u{FEFF}", "Hello! World!", 2; "Two interior BOMs")]
+    #[test_case("\u{FEFF}\u{FEFF}", "", 2; "Only BOMs")]
+    fn strip_interior_boms(input: &str, expected: &str, count: usize) {
+        let mut subject = TextData::from(input);
+
+        assert_eq!(subject.strip_interior_boms(), count);
+        assert_eq!(subject.data, expected);
+    }
+
+    #[test]
+    fn strip_interior_boms_after_decoding() {
+        let mut subject =
+            TextData::try_from(b"\xEF\xBB\xBFa\xEF\xBB\xBFb".as_slice()).expect("Should pass");
+
+        assert_eq!(subject.data, "a\u{FEFF}b");
+        assert_eq!(subject.strip_interior_boms(), 1);
+        assert_eq!(subject.data, "ab");
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test_case(b"\xC4\xE3\xBA\xC3", "gb18030", "你好", Encoding::Other("gb18030"); "GB18030")]
+    #[test_case(b"\x82\xB1\x82\xF1\x82\xC9\x82\xBF\x82\xCD", "shift_jis", "こんにちは", Encoding::Other("Shift_JIS"); "Shift-JIS")]
+    #[test_case(b"\x82\xB1\x82\xF1", "sjis", "こん", Encoding::Other("Shift_JIS"); "Shift-JIS alias")]
+    #[test_case(b"Caf\xE9", "latin1", "Café", Encoding::Windows1252; "Natively supported encoding")]
+    #[test_case(b"\xEF\xBB\xBFHello!", "shift_jis", "Hello!", Encoding::Utf8Bom; "BOM takes precedence")]
+    fn from_bytes_with_label(bytes: &[u8], label: &str, content: &str, encoding: Encoding) {
+        let subject = TextData::try_from_bytes_with_label(bytes, label).expect("Should pass");
+
+        assert_eq!(subject, TextData::new(content.into(), encoding));
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test_case(b"\x82", "shift_jis", "Invalid Shift_JIS sequence"; "Invalid Shift-JIS")]
+    #[test_case(b"Hello!", "ebcdic", "Unsupported encoding label \"ebcdic\""; "Unknown label")]
+    #[test_case(b"Hello!", "iso-2022-kr", "Unsupported encoding label \"iso-2022-kr\""; "Replacement encoding")]
+    fn from_bytes_with_label_invalid(bytes: &[u8], label: &str, message: &str) {
+        let subject = TextData::try_from_bytes_with_label(bytes, label).expect_err("Should fail");
+
+        assert_eq!(subject.to_string(), message);
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn from_bytes_with_label_round_trip() {
+        let bytes = b"\x82\xB1\x82\xF1\x82\xC9\x82\xBF\x82\xCD";
+        let content = FileContent::Encoded {
+            content: TextData::try_from_bytes_with_label(bytes, "shift_jis").expect("Should pass"),
+        };
+        let mut written = vec![];
+        content.write(&mut written).expect("Should pass");
+
+        assert_eq!(written, bytes);
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn decode_other_lossy() {
+        let subject = super::decode_lossy(b"\x82\xB1\x82 \x82", Encoding::Other("Shift_JIS"), '?');
+
+        assert_eq!(subject, "こ? ?");
+    }
+
+    #[test_case(0..6, Some("Hello!"); "ASCII chars")]
+    #[test_case(10..13, Some("好"); "Multi-byte char")]
+    #[test_case(7..8, None; "Inside a multi-byte char")]
+    #[test_case(15..18, None; "Ending inside a multi-byte char")]
+    #[test_case(0..100, None; "Out of bounds")]
+    fn slice(range: Range<usize>, expected: Option<&str>) {
+        let subject = TextData::from("Hello! 你好! 🌍");
+
+        assert_eq!(subject.slice(range), expected);
+    }
+
+    #[test_case("World", false, Some(9); "After multi-byte chars")]
+    #[test_case("world", false, None; "Case sensitive")]
+    #[test_case("wORLD", true, Some(9); "Case insensitive")]
+    #[test_case("🌍", false, Some(7); "Multi-byte needle")]
+    #[test_case("Ä", true, None; "Non-ASCII case insensitive")]
+    #[test_case("", false, Some(0); "Empty needle")]
+    #[test_case("World!", false, None; "Past the end")]
+    fn find(needle: &str, case_insensitive: bool, expected: Option<usize>) {
+        let bytes = to_utf16_le("你好, ä! 🌍 World 🌍");
+        let subject = TextData::try_from(bytes.as_slice()).expect("Should pass");
+
+        assert_eq!(subject.find(needle, case_insensitive), expected);
+    }
+
+    #[test]
+    fn find_in_empty() {
+        let subject = TextData::from("");
+
+        assert_eq!(subject.find("", false), Some(0));
+        assert_eq!(subject.find("a", true), None);
+    }
+
+    #[test]
+    fn char_indices() {
+        let subject = TextData::from("a你🌍");
+        let indices: Vec<_> = subject.char_indices().collect();
+
+        assert_eq!(indices, [(0, 'a'), (1, '你'), (4, '🌍')]);
+    }
+
+    #[test_case(Encoding::Utf8, &[0, 1, 4, 8]; "UTF-8")]
+    #[test_case(Encoding::Utf8Bom, &[3, 4, 7, 11]; "UTF-8-BOM")]
+    #[test_case(Encoding::Utf16Be, &[2, 4, 6, 10]; "UTF-16BE")]
+    #[test_case(Encoding::Utf16Le, &[2, 4, 6, 10]; "UTF-16LE")]
+    #[test_case(Encoding::Utf32Be, &[4, 8, 12, 16]; "UTF-32BE")]
+    #[test_case(Encoding::Utf32Le, &[4, 8, 12, 16]; "UTF-32LE")]
+    fn encoded_char_offsets(encoding: Encoding, expected: &[usize]) {
+        let subject = TextData::new("a你🌍b".into(), encoding);
+        let offsets: Vec<_> = subject.encoded_char_offsets().collect();
+        let bytes = encoding.encode(&subject.data).expect("Should pass");
+
+        assert_eq!(
+            offsets
+                .iter()
+                .map(|(offset, _)| *offset)
+                .collect::<Vec<_>>(),
+            expected
+        );
+        for (offset, c) in offsets {
+            let char_bytes = encoding.encode(&c.to_string()).expect("Should pass");
+            let bom_length = encoding.bom().map_or(0, <[u8]>::len);
+            assert!(
+                bytes[offset..].starts_with(&char_bytes[bom_length..]),
+                "{c}"
+            );
+        }
+    }
+
+    #[test_case(Encoding::Windows1252; "Windows-1252")]
+    #[test_case(Encoding::Cp037; "CP037")]
+    fn encoded_char_offsets_single_byte(encoding: Encoding) {
+        let subject = TextData::new("aéb".into(), encoding);
+        let offsets: Vec<_> = subject.encoded_char_offsets().collect();
+
+        assert_eq!(offsets, [(0, 'a'), (1, 'é'), (2, 'b')]);
+    }
+
+    #[test_case(b"Hello! \xE4\xBD\xA0\xE5\xA5\xBD! \xF0\x9F\x8C\x8D", Encoding::Utf8; "UTF-8")]
+    #[test_case(UTF8BOM_UNICODE_CONTENT, Encoding::Utf8Bom; "UTF-8-BOM")]
+    #[test_case(UTF16BE_UNICODE_CONTENT, Encoding::Utf16Be; "UTF-16BE")]
+    #[test_case(UTF16LE_UNICODE_CONTENT, Encoding::Utf16Le; "UTF-16LE")]
+    #[test_case(UTF32BE_UNICODE_CONTENT, Encoding::Utf32Be; "UTF-32BE")]
+    #[test_case(UTF32LE_UNICODE_CONTENT, Encoding::Utf32Le; "UTF-32LE")]
+    fn chars_from_bytes(bytes: &[u8], encoding: Encoding) {
+        let subject = TextData::try_chars_from_bytes(bytes).expect("Should pass");
+
+        assert_eq!(subject, ("Hello! 你好! 🌍".chars().collect(), encoding));
+    }
+
+    #[test_case(b"\xEF\xBB\xBFHello\xC1\x80", "Invalid UTF-8 sequence at byte offset 8"; "Invalid UTF-8 with BOM")]
+    #[test_case(b"\xFE\xFF\x00\x48\xD8\x3D\xD8\x3D\xDE\x0A", "Invalid UTF-16 sequence at byte offset 4"; "Invalid UTF-16BE")]
+    #[test_case(b"\x12\x34\0", "File content is binary"; "Binary")]
+    fn chars_from_invalid_bytes(bytes: &[u8], expected: &str) {
+        let subject = TextData::try_chars_from_bytes(bytes).expect_err("Should fail");
+
+        assert_eq!(subject.to_string(), expected);
+    }
+
+    #[test]
+    fn into_string() {
+        let subject = TextData::try_from(UTF16LE_UNICODE_CONTENT).expect("Should pass");
+
+        assert_eq!(subject.encoding(), Encoding::Utf16Le);
+        assert_eq!(subject.into_string(), "Hello! 你好! 🌍");
+    }
+
+    #[test]
+    fn from_string() {
+        let expected = TextData {
+            data: "Hello!\n".into(),
+            encoding: Encoding::Utf8,
+            line_ending: LineEnding::Lf,
+        };
+
+        assert_eq!(TextData::from_string("Hello!\n".into()), expected);
+        assert_eq!(TextData::from(String::from("Hello!\n")), expected);
+        assert_eq!(TextData::from("Hello!\n"), expected);
+    }
+
+    #[test]
+    fn with_encoding() {
+        let subject = TextData::from("Hello!").with_encoding(Encoding::Utf16Le);
+        let expected = TextData {
+            data: "Hello!".into(),
+            encoding: Encoding::Utf16Le,
+            line_ending: LineEnding::None,
+        };
+
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case(&UTF16LE_ASCII_CONTENT[2..], "Hello!", Encoding::Utf16Le; "UTF-16LE ASCII chars")]
+    #[test_case(&UTF16LE_UNICODE_CONTENT[2..], "Hello! 你好! 🌍", Encoding::Utf16Le; "UTF-16LE Unicode chars")]
+    #[test_case(&UTF16BE_ASCII_CONTENT[2..], "Hello!", Encoding::Utf16Be; "UTF-16BE ASCII chars")]
+    #[test_case(&UTF16BE_UNICODE_CONTENT[2..], "Hello! 你好! 🌍", Encoding::Utf16Be; "UTF-16BE Unicode chars")]
+    #[test_case(UTF16LE_UNICODE_CONTENT, "Hello! 你好! 🌍", Encoding::Utf16Le; "BOM is still detected")]
+    #[test_case(b"Hello!", "Hello!", Encoding::Utf8; "UTF-8")]
+    fn from_bomless_utf16(bytes: &[u8], content: &str, encoding: Encoding) {
+        let options = TextDataOptions {
+            allow_bomless_utf16: true,
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(bytes, options).expect("Should pass");
+
+        assert_eq!(subject, TextData::new(content.into(), encoding));
+    }
+
+    #[test_case(&UTF16LE_UNICODE_CONTENT[2..]; "UTF-16LE")]
+    #[test_case(&UTF16BE_UNICODE_CONTENT[2..]; "UTF-16BE")]
+    fn from_bomless_utf16_not_allowed(bytes: &[u8]) {
+        let subject = TextData::try_from_bytes_with_options(bytes, TextDataOptions::default());
+
+        assert!(matches!(subject, Err(TextDataError::Binary)));
+    }
+
+    #[test_case(&UTF16LE_ASCII_CONTENT[2..]; "UTF-16LE")]
+    #[test_case(&UTF16BE_ASCII_CONTENT[2..]; "UTF-16BE")]
+    #[test_case(b"H\x00e\x00l\x00l\x00o"; "Odd length UTF-16LE")]
+    fn from_periodic_utf16_not_allowed(bytes: &[u8]) {
+        let subject = TextData::try_from_bytes_with_options(bytes, TextDataOptions::default());
+
+        assert!(matches!(subject, Err(TextDataError::Binary)));
+    }
+
+    #[test]
+    fn from_periodic_utf16_with_custom_heuristic() {
+        let options = TextDataOptions {
+            binary_heuristic: BinaryHeuristic::Custom(|bytes| bytes.contains(&0)),
+            ..Default::default()
+        };
+        let subject = TextData::try_from_bytes_with_options(&UTF16LE_ASCII_CONTENT[2..], options);
+
+        assert!(matches!(subject, Err(TextDataError::Binary)));
+    }
+
+    #[test]
+    fn from_periodic_utf16_lossy() {
+        let subject = TextData::try_from_bytes_lossy(&UTF16LE_ASCII_CONTENT[2..]);
+
+        assert!(matches!(subject, Err(TextDataError::Binary)));
+    }
+
+    #[test_case(Encoding::Utf8, None)]
+    #[test_case(Encoding::Utf8Bom, Some(UTF8_BOM))]
+    #[test_case(Encoding::Utf16Be, Some(UTF16BE_BOM))]
+    #[test_case(Encoding::Utf16Le, Some(UTF16LE_BOM))]
+    #[test_case(Encoding::Utf32Be, Some(UTF32BE_BOM))]
+    #[test_case(Encoding::Utf32Le, Some(UTF32LE_BOM))]
+    #[test_case(Encoding::Windows1252, None)]
+    fn bom_bytes(encoding: Encoding, expected: Option<&[u8]>) {
+        let subject = TextData::from("Hello!").with_encoding(encoding);
+
+        assert_eq!(subject.bom_bytes(), expected);
+    }
+
+    #[test_case(b"Hello! \xE4\xBD\xA0\xE5\xA5\xBD! \xF0\x9F\x8C\x8D"; "UTF-8")]
+    #[test_case(UTF8BOM_UNICODE_CONTENT; "UTF-8 with BOM")]
+    #[test_case(UTF16BE_UNICODE_CONTENT; "UTF-16BE")]
+    #[test_case(UTF16LE_UNICODE_CONTENT; "UTF-16LE")]
+    #[test_case(UTF32BE_UNICODE_CONTENT; "UTF-32BE")]
+    #[test_case(UTF32LE_UNICODE_CONTENT; "UTF-32LE")]
+    fn from_valid_vec(bytes: &[u8]) {
+        let subject = TextData::try_from(bytes.to_vec()).expect("Should pass");
+
+        assert_eq!(subject, TextData::try_from(bytes).expect("Should pass"));
+    }
+
+    #[test_case(b"Hello\xC1\x80"; "Invalid UTF-8")]
+    #[test_case(b"\xEF\xBB\xBFHello\xC1\x80"; "Invalid UTF-8 with BOM")]
+    #[test_case(b"\xFE\xFF\xD8\xA5"; "Invalid UTF-16BE")]
+    #[test_case(b"\x12\x34\0"; "Binary")]
+    fn from_invalid_vec(bytes: &[u8]) {
+        let subject = TextData::try_from(bytes.to_vec()).expect_err("Should fail");
+        let expected = TextData::try_from(bytes).expect_err("Should fail");
+
+        assert_eq!(subject.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn from_vec_reuses_allocation() {
+        let bytes = b"Hello!".to_vec();
+        let pointer = bytes.as_ptr();
+        let subject = TextData::try_from(bytes).expect("Should pass");
+
+        assert_eq!(subject.data.as_ptr(), pointer);
+    }
+
+    #[test]
+    fn borrow_utf8() {
+        let bytes = "Hello! 你好! 🌍".as_bytes();
+        let subject = TextData::borrow_utf8(bytes).expect("Should pass");
+
+        assert_eq!(
+            subject,
+            BorrowedTextData {
+                data: "Hello! 你好! 🌍",
+                encoding: Encoding::Utf8,
+            }
+        );
+        assert_eq!(subject.data.as_ptr(), bytes.as_ptr());
+    }
+
+    #[test_case(UTF8BOM_ASCII_CONTENT, Encoding::Utf8Bom; "UTF-8 with BOM")]
+    #[test_case(UTF16LE_UNICODE_CONTENT, Encoding::Utf16Le; "UTF-16LE")]
+    fn borrow_utf8_not_borrowable(bytes: &[u8], encoding: Encoding) {
+        let subject = TextData::borrow_utf8(bytes);
+
+        assert!(matches!(subject, Err(TextDataError::NotBorrowable(e)) if e == encoding));
+    }
+
+    #[test_case(b"Caf\xE9", "Invalid UTF-8 sequence at byte offset 3"; "invalid UTF-8")]
+    #[test_case(&[1, 2, 3, 0], "File content is binary"; "binary")]
+    fn borrow_utf8_invalid(bytes: &[u8], expected: &str) {
+        let subject = TextData::borrow_utf8(bytes).expect_err("Should fail");
+
+        assert_eq!(subject.to_string(), expected);
+    }
 }
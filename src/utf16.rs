@@ -1,12 +1,22 @@
+use crate::encoding::Encoding;
+
 #[derive(Debug, PartialEq, thiserror::Error)]
-#[error("Uneven length byte sequence")]
-pub struct UnevenByteSequenceError;
+#[error("{encoding} body has odd length {len}")]
+pub struct UnevenByteSequenceError {
+    /// The length of the body in bytes, without the BOM
+    pub len: usize,
+    /// The UTF-16 encoding the body was being decoded as, [Encoding::Utf16Be] or [Encoding::Utf16Le]
+    pub encoding: Encoding,
+}
 
 /// Converts a vector of big-endian encoded bytes into a vector of corresponding u16 values
-/// UnevenByteSequenceError will be returned if the input has an uneven length
+/// UnevenByteSequenceError will be returned with the length of the input if it's uneven
 pub fn to_u16_be(input: &[u8]) -> Result<Vec<u16>, UnevenByteSequenceError> {
-    if input.len() % 2 != 0 {
-        Err(UnevenByteSequenceError)
+    if !input.len().is_multiple_of(2) {
+        Err(UnevenByteSequenceError {
+            len: input.len(),
+            encoding: Encoding::Utf16Be,
+        })
     } else {
         Ok(input
             .chunks(2)
@@ -20,10 +30,13 @@ pub fn to_u16_be(input: &[u8]) -> Result<Vec<u16>, UnevenByteSequenceError> {
 }
 
 /// Converts a vector of little-endian encoded bytes into a vector of corresponding u16 values
-/// UnevenByteSequenceError will be returned if the input has an uneven length
+/// UnevenByteSequenceError will be returned with the length of the input if it's uneven
 pub fn to_u16_le(input: &[u8]) -> Result<Vec<u16>, UnevenByteSequenceError> {
-    if input.len() % 2 != 0 {
-        Err(UnevenByteSequenceError)
+    if !input.len().is_multiple_of(2) {
+        Err(UnevenByteSequenceError {
+            len: input.len(),
+            encoding: Encoding::Utf16Le,
+        })
     } else {
         Ok(input
             .chunks(2)
@@ -40,6 +53,7 @@ pub fn to_u16_le(input: &[u8]) -> Result<Vec<u16>, UnevenByteSequenceError> {
 mod tests {
     use test_case::test_case;
 
+    use crate::encoding::Encoding;
     use crate::utf16::{to_u16_be, to_u16_le, UnevenByteSequenceError};
 
     #[test_case(b"", &[])]
@@ -64,13 +78,33 @@ mod tests {
     fn invalid_be() {
         let bytes = b"\x12\x34\x56";
         let subject = to_u16_be(bytes);
-        assert_eq!(subject, Err(UnevenByteSequenceError));
+        assert_eq!(
+            subject,
+            Err(UnevenByteSequenceError {
+                len: 3,
+                encoding: Encoding::Utf16Be
+            })
+        );
+        assert_eq!(
+            subject.unwrap_err().to_string(),
+            "UTF-16-BE body has odd length 3"
+        );
     }
 
     #[test]
     fn invalid_le() {
         let bytes = b"\x12\x34\x56";
         let subject = to_u16_le(bytes);
-        assert_eq!(subject, Err(UnevenByteSequenceError));
+        assert_eq!(
+            subject,
+            Err(UnevenByteSequenceError {
+                len: 3,
+                encoding: Encoding::Utf16Le
+            })
+        );
+        assert_eq!(
+            subject.unwrap_err().to_string(),
+            "UTF-16-LE body has odd length 3"
+        );
     }
 }
@@ -0,0 +1,74 @@
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error("Byte sequence length is not a multiple of 4")]
+pub struct MisalignedByteSequenceError;
+
+/// Converts a vector of big-endian encoded bytes into a vector of corresponding u32 values
+/// MisalignedByteSequenceError will be returned if the input length is not a multiple of 4
+pub fn to_u32_be(input: &[u8]) -> Result<Vec<u32>, MisalignedByteSequenceError> {
+    if !input.len().is_multiple_of(4) {
+        Err(MisalignedByteSequenceError)
+    } else {
+        Ok(input
+            .chunks(4)
+            .map(|chunk| {
+                let mut buf = [0; 4];
+                buf.copy_from_slice(chunk);
+                u32::from_be_bytes(buf)
+            })
+            .collect())
+    }
+}
+
+/// Converts a vector of little-endian encoded bytes into a vector of corresponding u32 values
+/// MisalignedByteSequenceError will be returned if the input length is not a multiple of 4
+pub fn to_u32_le(input: &[u8]) -> Result<Vec<u32>, MisalignedByteSequenceError> {
+    if !input.len().is_multiple_of(4) {
+        Err(MisalignedByteSequenceError)
+    } else {
+        Ok(input
+            .chunks(4)
+            .map(|chunk| {
+                let mut buf = [0; 4];
+                buf.copy_from_slice(chunk);
+                u32::from_le_bytes(buf)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::utf32::{to_u32_be, to_u32_le, MisalignedByteSequenceError};
+
+    #[test_case(b"", &[])]
+    #[test_case(b"\x00\x01\xF3\x0D", &[0x0001F30D])]
+    #[test_case(b"\x00\x00\x00\x48\x00\x00\x00\x69", &[0x48, 0x69])]
+    fn valid_be(bytes: &[u8], expected: &[u32]) {
+        let subject = to_u32_be(bytes).expect("Should pass");
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case(b"", &[])]
+    #[test_case(b"\x0D\xF3\x01\x00", &[0x0001F30D])]
+    #[test_case(b"\x48\x00\x00\x00\x69\x00\x00\x00", &[0x48, 0x69])]
+    fn valid_le(bytes: &[u8], expected: &[u32]) {
+        let subject = to_u32_le(bytes).expect("Should pass");
+        assert_eq!(subject, expected);
+    }
+
+    #[test_case(b"\x00"; "1 byte")]
+    #[test_case(b"\x00\x00\x00\x48\x00\x00"; "6 bytes")]
+    fn invalid_be(bytes: &[u8]) {
+        let subject = to_u32_be(bytes);
+        assert_eq!(subject, Err(MisalignedByteSequenceError));
+    }
+
+    #[test_case(b"\x00"; "1 byte")]
+    #[test_case(b"\x48\x00\x00\x00\x69\x00"; "6 bytes")]
+    fn invalid_le(bytes: &[u8]) {
+        let subject = to_u32_le(bytes);
+        assert_eq!(subject, Err(MisalignedByteSequenceError));
+    }
+}
@@ -0,0 +1,190 @@
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error("Invalid UTF-7 sequence at byte offset {offset}")]
+pub struct InvalidUtf7Error {
+    pub offset: usize,
+}
+
+/// The characters that are written as themselves rather than in a shifted (base64) sequence.
+/// This is Set D and Set O from RFC 2152 plus space, tab, CR and LF. `\` and `~` are left out of
+/// Set O, as they're commonly remapped in 7-bit character sets.
+const DIRECT_CHARS: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789'(),-./:? \t\r\n!\"#$%&*;<=>@[]^_`{|}";
+
+/// The modified base64 alphabet used in shifted sequences, which has no padding character
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Decodes UTF-7 bytes (RFC 2152) into a [String].
+/// InvalidUtf7Error will be returned with the offset of the first byte that isn't 7-bit ASCII, or
+/// the `+` that starts the first shifted sequence that doesn't decode to valid UTF-16.
+pub fn decode(bytes: &[u8]) -> Result<String, InvalidUtf7Error> {
+    decode_with_replacement(bytes, None)
+}
+
+/// Decodes UTF-7 bytes (RFC 2152) into a [String], replacing invalid bytes and shifted sequences
+/// with the `replacement`, e.g. U+FFFD
+pub fn decode_lossy(bytes: &[u8], replacement: char) -> String {
+    decode_with_replacement(bytes, Some(replacement)).expect("Invalid sequences are replaced")
+}
+
+fn decode_with_replacement(
+    bytes: &[u8],
+    replacement: Option<char>,
+) -> Result<String, InvalidUtf7Error> {
+    let mut data = String::with_capacity(bytes.len());
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let (decoded, length) = match bytes[offset] {
+            b'+' if bytes.get(offset + 1) == Some(&b'-') => (Some("+".into()), 2),
+            b'+' => decode_shifted(&bytes[offset + 1..]),
+            byte if byte.is_ascii() => (Some((byte as char).into()), 1),
+            _ => (None, 1),
+        };
+
+        match (decoded, replacement) {
+            (Some(decoded), _) => data.push_str(&decoded),
+            (None, Some(replacement)) => data.push(replacement),
+            (None, None) => return Err(InvalidUtf7Error { offset }),
+        }
+        offset += length;
+    }
+
+    Ok(data)
+}
+
+/// Decodes the shifted sequence at the start of the bytes (after the `+`), returning the decoded
+/// content, or None if it's invalid, along with the number of bytes it took up including the `+`
+/// and any `-` that ends it
+fn decode_shifted(bytes: &[u8]) -> (Option<String>, usize) {
+    let length = bytes
+        .iter()
+        .position(|byte| !BASE64_CHARS.contains(byte))
+        .unwrap_or(bytes.len());
+    let terminator = usize::from(bytes.get(length) == Some(&b'-'));
+
+    let mut units = Vec::with_capacity(length * 6 / 16);
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    for byte in &bytes[..length] {
+        let value = BASE64_CHARS
+            .iter()
+            .position(|c| c == byte)
+            .unwrap_or_default();
+        bits = (bits << 6) | value as u32;
+        bit_count += 6;
+        if bit_count >= 16 {
+            bit_count -= 16;
+            units.push((bits >> bit_count) as u16);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+
+    // A shifted sequence can't be empty, and any bits left over after the last unit must be zero
+    let decoded = if units.is_empty() || bits != 0 {
+        None
+    } else {
+        String::from_utf16(&units).ok()
+    };
+
+    (decoded, 1 + length + terminator)
+}
+
+/// Encodes a [str] into UTF-7 bytes (RFC 2152).
+/// `+` is written as `+-`, and every other character outside of the direct set is written in a
+/// shifted sequence, which is only ended with an explicit `-` when it's needed to be unambiguous.
+pub fn encode(s: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '+' {
+            bytes.extend_from_slice(b"+-");
+        } else if is_direct(c) {
+            bytes.push(c as u8);
+        } else {
+            let mut units = vec![];
+            let mut buffer = [0u16; 2];
+            units.extend_from_slice(c.encode_utf16(&mut buffer));
+            while let Some(c) = chars.next_if(|c| *c != '+' && !is_direct(*c)) {
+                units.extend_from_slice(c.encode_utf16(&mut buffer));
+            }
+
+            bytes.push(b'+');
+            encode_shifted(&units, &mut bytes);
+            match chars.peek() {
+                Some(next) if !BASE64_CHARS.contains(&(*next as u8)) && *next != '-' => {}
+                _ => bytes.push(b'-'),
+            }
+        }
+    }
+
+    bytes
+}
+
+fn is_direct(c: char) -> bool {
+    c.is_ascii() && DIRECT_CHARS.contains(&(c as u8))
+}
+
+/// Writes the UTF-16 units in modified base64, padding the final character with zero bits
+fn encode_shifted(units: &[u16], bytes: &mut Vec<u8>) {
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    for unit in units {
+        bits = (bits << 16) | *unit as u32;
+        bit_count += 16;
+        while bit_count >= 6 {
+            bit_count -= 6;
+            bytes.push(BASE64_CHARS[((bits >> bit_count) & 0x3F) as usize]);
+        }
+        bits &= (1 << bit_count) - 1;
+    }
+
+    if bit_count > 0 {
+        bytes.push(BASE64_CHARS[((bits << (6 - bit_count)) & 0x3F) as usize]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::utf7::{decode, decode_lossy, encode, InvalidUtf7Error};
+
+    // The examples from RFC 2152, and the `+-` escape for a literal `+`
+    #[test_case(b"A+ImIDkQ.", "A\u{2262}\u{391}."; "example 1")]
+    #[test_case(b"Hi Mom -+Jjo--!", "Hi Mom -\u{263A}-!"; "example 2")]
+    #[test_case(b"+ZeVnLIqe-", "日本語"; "example 3")]
+    #[test_case(b"1 +- 1 = 2", "1 + 1 = 2"; "escaped plus sign")]
+    #[test_case(b"+2DzfDQ-", "🌍"; "surrogate pair")]
+    #[test_case(b"", ""; "no content")]
+    fn round_trip(bytes: &[u8], s: &str) {
+        assert_eq!(decode(bytes), Ok(s.into()));
+        assert_eq!(encode(s), bytes);
+    }
+
+    #[test_case(b"Hi Mom +Jjo-", "Hi Mom \u{263A}"; "explicit terminator at the end")]
+    #[test_case(b"Hi Mom +Jjo", "Hi Mom \u{263A}"; "no terminator at the end")]
+    #[test_case(b"~\\", "~\\"; "optional direct chars")]
+    fn decode_valid(bytes: &[u8], expected: &str) {
+        assert_eq!(decode(bytes), Ok(expected.into()));
+    }
+
+    #[test_case(b"Caf\xE9", 3; "8-bit byte")]
+    #[test_case(b"Hi +-+ ", 5; "empty shifted sequence")]
+    #[test_case(b"1+1=2", 1; "non-zero leftover bits")]
+    #[test_case(b"Hi +2D0-", 3; "unpaired surrogate")]
+    fn decode_invalid(bytes: &[u8], offset: usize) {
+        assert_eq!(decode(bytes), Err(InvalidUtf7Error { offset }));
+    }
+
+    #[test]
+    fn decode_invalid_lossy() {
+        assert_eq!(
+            decode_lossy(b"Caf\xE9 +2D0-!", char::REPLACEMENT_CHARACTER),
+            "Caf\u{FFFD} \u{FFFD}!"
+        );
+    }
+
+    #[test]
+    fn encode_non_ascii() {
+        assert_eq!(encode("Café~"), b"Caf+AOkAfg-");
+    }
+}
@@ -0,0 +1,60 @@
+/// The characters for bytes 0x80 to 0x9F, which is the only range where Windows-1252 differs from
+/// Latin-1. The five bytes left undefined by the codepage map to the C1 control character with the
+/// same value, matching the behaviour of Windows and the WHATWG Encoding Standard.
+const HIGH_CONTROL_RANGE: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Converts a Windows-1252 byte into its corresponding [char]. Every byte maps to a [char].
+pub fn to_char(byte: u8) -> char {
+    match byte {
+        0x80..=0x9F => HIGH_CONTROL_RANGE[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+/// Converts a [char] into its corresponding Windows-1252 byte.
+/// None will be returned if the character can't be represented in Windows-1252
+pub fn from_char(c: char) -> Option<u8> {
+    match c as u32 {
+        0x00..=0x7F | 0xA0..=0xFF => Some(c as u8),
+        _ => HIGH_CONTROL_RANGE
+            .iter()
+            .position(|mapped| *mapped == c)
+            .map(|index| 0x80 + index as u8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::windows1252::{from_char, to_char};
+
+    #[test_case(b'A', 'A'; "ascii")]
+    #[test_case(0x80, '€'; "euro sign")]
+    #[test_case(0x9F, 'Ÿ'; "end of the high control range")]
+    #[test_case(0x81, '\u{0081}'; "undefined byte")]
+    #[test_case(0xE9, 'é'; "latin-1")]
+    fn round_trip(byte: u8, c: char) {
+        assert_eq!(to_char(byte), c);
+        assert_eq!(from_char(c), Some(byte));
+    }
+
+    #[test_case('\u{0080}'; "C1 control replaced by the euro sign")]
+    #[test_case('你'; "mandarin")]
+    #[test_case('🌍'; "emoji")]
+    fn unmappable(c: char) {
+        assert_eq!(from_char(c), None);
+    }
+
+    #[test]
+    fn every_byte_round_trips() {
+        for byte in u8::MIN..=u8::MAX {
+            assert_eq!(from_char(to_char(byte)), Some(byte));
+        }
+    }
+}
@@ -1,6 +1,17 @@
+/// Writes the bytes to a file of the given name in the temporary directory for integration tests.
+/// Tests that read a fixture use a copy of it, as other tests save over the fixtures while running.
+#[cfg(test)]
+fn temp_file(name: &str, bytes: &[u8]) -> std::io::Result<String> {
+    let path = format!("{}/{name}", env!("CARGO_TARGET_TMPDIR"));
+    std::fs::write(&path, bytes)?;
+    Ok(path)
+}
+
 #[cfg(test)]
 mod file_io_tests {
-    use file_content::{Encoding, File, FileContent, TextData};
+    use file_content::{
+        Encoding, File, FileContent, FileError, GitAttributes, LineEnding, TextData, TextDataError,
+    };
     use std::fs;
     use test_case::test_case;
 
@@ -11,6 +22,8 @@ mod file_io_tests {
     #[test_case("UTF8BOM/unicode", Encoding::Utf8Bom; "UTF-8WithBom")]
     #[test_case("UTF16BE/unicode", Encoding::Utf16Be; "UTF-16BE")]
     #[test_case("UTF16LE/unicode", Encoding::Utf16Le; "UTF-16LE")]
+    #[test_case("UTF32BE/unicode", Encoding::Utf32Be; "UTF-32BE")]
+    #[test_case("UTF32LE/unicode", Encoding::Utf32Le; "UTF-32LE")]
     fn save_encoded_content(path: &str, encoding: Encoding) -> anyhow::Result<()> {
         let path = format!("{ENCODED_FILES_ROOT}/{path}");
 
@@ -22,6 +35,7 @@ mod file_io_tests {
                 content: TextData {
                     data: FILE_CONTENT.into(),
                     encoding,
+                    line_ending: LineEnding::None,
                 },
             },
         };
@@ -35,6 +49,535 @@ mod file_io_tests {
         Ok(())
     }
 
+    #[test]
+    fn save_converted_content() -> anyhow::Result<()> {
+        let path = super::temp_file("convert_utf16le", include_bytes!("data/UTF16LE/unicode"))?;
+        let mut file = File::new_from_path(path)?;
+        file.convert_to(Encoding::Utf8)?;
+        file.save_to_path()?;
+
+        let bytes_after_saving = fs::read(&file.path)?;
+
+        assert_eq!(bytes_after_saving, FILE_CONTENT.as_bytes());
+
+        Ok(())
+    }
+
+    #[test_case("write_utf8", include_bytes!("data/UTF8/unicode"); "UTF-8")]
+    #[test_case("write_utf16be", include_bytes!("data/UTF16BE/unicode"); "UTF-16BE")]
+    #[test_case("write_utf32le", include_bytes!("data/UTF32LE/unicode"); "UTF-32LE")]
+    #[test_case("write_binary", include_bytes!("data/Binary/binary"); "Binary")]
+    fn write_to_other_path(name: &str, bytes: &[u8]) -> anyhow::Result<()> {
+        let path = super::temp_file(name, bytes)?;
+        let target = format!("{path}_copy");
+        let file = File::new_from_path(&path)?;
+        file.write_to_path(&target)?;
+
+        assert_eq!(fs::read(&target)?, bytes);
+        assert_eq!(file.path, std::path::PathBuf::from(path));
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_atomically() -> anyhow::Result<()> {
+        let directory = format!("{}/save_atomically", env!("CARGO_TARGET_TMPDIR"));
+        fs::create_dir_all(&directory)?;
+        let path = format!("{directory}/file");
+        fs::write(&path, include_bytes!("data/UTF16LE/unicode"))?;
+
+        let mut file = File::new_from_path(&path)?;
+        file.convert_to(Encoding::Utf8)?;
+        file.save_atomically()?;
+
+        assert_eq!(fs::read(&path)?, FILE_CONTENT.as_bytes());
+        assert_eq!(fs::read_dir(&directory)?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_atomically_failure() -> anyhow::Result<()> {
+        let directory = format!("{}/save_atomically_failure", env!("CARGO_TARGET_TMPDIR"));
+        fs::create_dir_all(&directory)?;
+        let path = format!("{directory}/file");
+        fs::write(&path, include_bytes!("data/UTF8/unicode"))?;
+
+        // The content can't be encoded as Windows-1252, so writing fails
+        let mut file = File::new_from_path(&path)?;
+        file.convert_to(Encoding::Windows1252)?;
+        file.save_atomically().expect_err("Should fail");
+
+        assert_eq!(fs::read(&path)?, FILE_CONTENT.as_bytes());
+        assert_eq!(fs::read_dir(&directory)?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test_case(false, &["binary", "text"]; "not recursive")]
+    #[test_case(true, &["binary", "nested/text", "text"]; "recursive")]
+    fn read_dir(recursive: bool, expected: &[&str]) -> anyhow::Result<()> {
+        let directory = format!("{}/read_dir_{recursive}", env!("CARGO_TARGET_TMPDIR"));
+        fs::create_dir_all(format!("{directory}/nested"))?;
+        fs::write(format!("{directory}/text"), FILE_CONTENT)?;
+        fs::write(format!("{directory}/binary"), [1, 2, 3, 0, 4, 5])?;
+        fs::write(format!("{directory}/nested/text"), FILE_CONTENT)?;
+        #[cfg(unix)]
+        if fs::symlink_metadata(format!("{directory}/link")).is_err() {
+            std::os::unix::fs::symlink("text", format!("{directory}/link"))?;
+        }
+
+        let mut files = File::read_dir(&directory, recursive).collect::<Result<Vec<_>, _>>()?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let paths: Vec<_> = files
+            .iter()
+            .map(|file| file.path.strip_prefix(&directory))
+            .collect::<Result<_, _>>()?;
+
+        assert_eq!(paths, expected);
+        assert!(matches!(files[0].content, FileContent::Binary { .. }));
+        assert!(files[1..].iter().all(|file| matches!(
+            &file.content,
+            FileContent::Encoded { content } if content.data == FILE_CONTENT
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_missing_dir() {
+        let directory = format!("{}/read_missing_dir", env!("CARGO_TARGET_TMPDIR"));
+        let results: Vec<_> = File::read_dir(directory, true).collect();
+
+        assert_eq!(results.len(), 1);
+        assert!(
+            matches!(&results[0], Err(FileError::Io { path: Some(path), .. }) if path.ends_with("read_missing_dir"))
+        );
+    }
+
+    #[test]
+    fn read_content_to_string() -> anyhow::Result<()> {
+        let path = super::temp_file("utf16le_to_string", include_bytes!("data/UTF16LE/unicode"))?;
+        let content = file_content::read_to_string(path)?;
+
+        assert_eq!(content, FILE_CONTENT);
+
+        Ok(())
+    }
+
+    #[test_case("utf8bom_keep_bom", include_bytes!("data/UTF8BOM/unicode"), true, "\u{FEFF}Hello! 你好! 🌍"; "UTF-8-BOM keeping the BOM")]
+    #[test_case("utf8bom_strip_bom", include_bytes!("data/UTF8BOM/unicode"), false, FILE_CONTENT; "UTF-8-BOM stripping the BOM")]
+    #[test_case("utf16le_keep_bom", include_bytes!("data/UTF16LE/unicode"), true, "\u{FEFF}Hello! 你好! 🌍"; "UTF-16LE keeping the BOM")]
+    #[test_case("utf8_keep_bom", include_bytes!("data/UTF8/unicode"), true, FILE_CONTENT; "UTF-8 without a BOM")]
+    fn read_content_to_string_with_bom(
+        name: &str,
+        bytes: &[u8],
+        keep_bom: bool,
+        expected: &str,
+    ) -> anyhow::Result<()> {
+        let path = super::temp_file(name, bytes)?;
+        let content = file_content::read_to_string_with_bom(&path, keep_bom)?;
+
+        assert_eq!(content, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_content_to_string_like_std() -> anyhow::Result<()> {
+        let path = super::temp_file("utf8bom_like_std", include_bytes!("data/UTF8BOM/unicode"))?;
+
+        assert_eq!(
+            file_content::read_to_string_with_bom(&path, true)?,
+            fs::read_to_string(&path)?
+        );
+
+        Ok(())
+    }
+
+    #[test_case("line_range_utf8", Encoding::Utf8; "UTF-8")]
+    #[test_case("line_range_utf16le", Encoding::Utf16Le; "UTF-16LE")]
+    #[test_case("line_range_utf32be", Encoding::Utf32Be; "UTF-32BE")]
+    fn read_line_range(name: &str, encoding: Encoding) -> anyhow::Result<()> {
+        let lines: Vec<String> = (0..100).map(|i| format!("{i}: {FILE_CONTENT}")).collect();
+        let file = File::from_string(name, lines.join("\r\n"), encoding);
+        let path = super::temp_file(name, &file.content.to_bytes()?)?;
+
+        assert_eq!(file_content::read_line_range(&path, 40, 45)?, lines[40..45]);
+        assert_eq!(file_content::read_line_range(&path, 98, 120)?, lines[98..]);
+
+        Ok(())
+    }
+
+    #[test_case("line_range_periodic_utf16le", &include_bytes!("data/UTF16LE/ascii")[2..]; "Periodic UTF-16LE")]
+    #[test_case("line_range_periodic_utf16be", &include_bytes!("data/UTF16BE/ascii")[2..]; "Periodic UTF-16BE")]
+    #[test_case("line_range_truncated_bom", b"\xFF"; "Truncated BOM")]
+    #[test_case("line_range_multiple_boms", b"\xEF\xBB\xBF\xFF\xFEH\x00"; "Multiple BOMs")]
+    #[test_case("line_range_binary", include_bytes!("data/Binary/binary"); "Binary")]
+    fn read_line_range_like_new_from_path(name: &str, bytes: &[u8]) -> anyhow::Result<()> {
+        let path = super::temp_file(name, bytes)?;
+        let subject = file_content::read_line_range(&path, 0, usize::MAX);
+
+        match File::new_from_path(&path)?.content {
+            FileContent::Encoded { content } => {
+                assert_eq!(subject?, content.lines().collect::<Vec<_>>());
+            }
+            FileContent::Binary { .. } => {
+                assert!(matches!(subject, Err(FileError::TextData(_))));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_content_to_text_data() -> anyhow::Result<()> {
+        let path = super::temp_file(
+            "utf16le_to_text_data",
+            include_bytes!("data/UTF16LE/unicode"),
+        )?;
+        let content = file_content::read_to_text_data(path)?;
+
+        assert_eq!(content.data, FILE_CONTENT);
+        assert_eq!(content.encoding, Encoding::Utf16Le);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_content_from_reader() -> anyhow::Result<()> {
+        let bytes: &[u8] = include_bytes!("data/UTF32BE/unicode");
+        let content = file_content::read_from_reader(bytes)?;
+
+        assert_eq!(content, FILE_CONTENT);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_text_data_from_reader() -> anyhow::Result<()> {
+        let bytes = std::io::Cursor::new(include_bytes!("data/UTF16LE/unicode"));
+        let content = file_content::read_text_data_from_reader(bytes)?;
+
+        assert_eq!(content.data, FILE_CONTENT);
+        assert_eq!(content.encoding, Encoding::Utf16Le);
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_file_from_string() -> anyhow::Result<()> {
+        let path = format!("{}/from_string", env!("CARGO_TARGET_TMPDIR"));
+        let file = File::from_string(&path, FILE_CONTENT.into(), Encoding::Utf16Le);
+        file.save_to_path()?;
+
+        assert_eq!(fs::read(&path)?, include_bytes!("data/UTF16LE/unicode"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_file_counted() -> anyhow::Result<()> {
+        let path = format!("{}/save_counted", env!("CARGO_TARGET_TMPDIR"));
+        let file = File::from_string(&path, FILE_CONTENT.into(), Encoding::Utf16Le);
+        let subject = file.save_to_path_counted()?;
+
+        assert_eq!(subject, include_bytes!("data/UTF16LE/unicode").len());
+        assert_eq!(subject, fs::metadata(&path)?.len() as usize);
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_file_from_bytes() -> anyhow::Result<()> {
+        // Valid UTF-8 is kept as binary content, as no detection is done
+        let path = format!("{}/from_bytes_binary", env!("CARGO_TARGET_TMPDIR"));
+        let file = File::from_bytes_binary(&path, FILE_CONTENT.into());
+        file.save_to_path()?;
+
+        assert!(matches!(file.content, FileContent::Binary { .. }));
+        assert_eq!(fs::read(&path)?, FILE_CONTENT.as_bytes());
+
+        Ok(())
+    }
+
+    #[test_case("round_trip_utf8bom", include_bytes!("data/UTF8BOM/empty"), Encoding::Utf8Bom; "UTF-8WithBom")]
+    #[test_case("round_trip_utf16be", include_bytes!("data/UTF16BE/empty"), Encoding::Utf16Be; "UTF-16BE")]
+    #[test_case("round_trip_utf16le", include_bytes!("data/UTF16LE/empty"), Encoding::Utf16Le; "UTF-16LE")]
+    #[test_case("round_trip_utf32be", include_bytes!("data/UTF32BE/empty"), Encoding::Utf32Be; "UTF-32BE")]
+    #[test_case("round_trip_utf32le", include_bytes!("data/UTF32LE/empty"), Encoding::Utf32Le; "UTF-32LE")]
+    fn round_trip_empty_content(
+        name: &str,
+        bytes: &[u8],
+        encoding: Encoding,
+    ) -> anyhow::Result<()> {
+        let path = super::temp_file(name, bytes)?;
+        let file = File::new_from_path(&path)?;
+
+        assert_eq!(
+            file.content,
+            FileContent::Encoded {
+                content: TextData {
+                    data: String::new(),
+                    encoding,
+                    line_ending: LineEnding::None,
+                },
+            }
+        );
+        assert!(file.is_empty());
+        assert!(file.round_trips());
+        file.save_to_path()?;
+        assert_eq!(fs::read(&path)?, bytes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_changed_content() -> anyhow::Result<()> {
+        let path = super::temp_file("round_trip_changed", include_bytes!("data/UTF16LE/unicode"))?;
+        let mut file = File::new_from_path(&path)?;
+
+        assert!(file.round_trips());
+        file.convert_to(Encoding::Utf8)?;
+        assert!(!file.round_trips());
+
+        Ok(())
+    }
+
+    #[test]
+    fn edit_text_and_save() -> anyhow::Result<()> {
+        let path = super::temp_file("edit_text", include_bytes!("data/UTF16BE/ascii"))?;
+        let mut file = File::new_from_path(&path)?;
+        let text = file.text().map(|text| text.replace("Hello", "Goodbye"));
+        file.set_text(text.unwrap_or_default());
+        file.save_to_path()?;
+
+        assert_eq!(
+            fs::read(&path)?,
+            b"\xFE\xFF\x00G\x00o\x00o\x00d\x00b\x00y\x00e\x00!"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn map_text_and_save() -> anyhow::Result<()> {
+        let path = super::temp_file("map_text", include_bytes!("data/UTF16LE/ascii"))?;
+        let mut file = File::new_from_path(&path)?;
+        file.map_text(str::to_uppercase)?;
+        file.save_to_path()?;
+
+        assert_eq!(fs::read(&path)?, b"\xFF\xFEH\x00E\x00L\x00L\x00O\x00!\x00");
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_missing_file() {
+        let path = format!("{}/read_missing_file", env!("CARGO_TARGET_TMPDIR"));
+        let subject = File::new_from_path(&path).expect_err("Should fail");
+
+        assert!(subject.to_string().starts_with(&format!("{path}: ")));
+        assert!(matches!(subject, FileError::Io { path: Some(_), .. }));
+    }
+
+    #[test]
+    fn read_directory() {
+        let path = env!("CARGO_TARGET_TMPDIR");
+        let subject = File::new_from_path(path).expect_err("Should fail");
+
+        assert!(matches!(&subject, FileError::NotAFile(dir) if dir.as_os_str() == path));
+        assert_eq!(
+            subject.to_string(),
+            format!("{path}: Is a directory, not a file")
+        );
+    }
+
+    #[test]
+    fn read_empty_path() {
+        let subject = File::new_from_path("").expect_err("Should fail");
+
+        assert!(matches!(subject, FileError::EmptyPath));
+    }
+
+    #[test_case(include_bytes!("data/UTF8/unicode"); "UTF-8")]
+    #[test_case(include_bytes!("data/UTF8BOM/unicode"); "UTF-8 with BOM")]
+    #[test_case(include_bytes!("data/UTF16BE/unicode"); "UTF-16BE")]
+    #[test_case(include_bytes!("data/UTF16LE/unicode"); "UTF-16LE")]
+    #[test_case(include_bytes!("data/UTF32BE/unicode"); "UTF-32BE")]
+    #[test_case(include_bytes!("data/UTF32LE/unicode"); "UTF-32LE")]
+    #[test_case(include_bytes!("data/UTF16LE/empty"); "Empty UTF-16LE")]
+    #[test_case(include_bytes!("data/Binary/binary"); "Binary")]
+    fn content_to_bytes(bytes: &[u8]) -> anyhow::Result<()> {
+        let file = File::new("foo.txt", bytes)?;
+
+        assert_eq!(file.content.to_bytes()?, bytes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn convert_file() -> anyhow::Result<()> {
+        let src = super::temp_file("convert_utf16be", include_bytes!("data/UTF16BE/unicode"))?;
+        let dst = format!("{}/convert_utf16be_to_utf8", env!("CARGO_TARGET_TMPDIR"));
+        file_content::convert_file(&src, &dst, Encoding::Utf8)?;
+
+        assert_eq!(fs::read(&dst)?, include_bytes!("data/UTF8/unicode"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn convert_binary_file() -> anyhow::Result<()> {
+        let src = super::temp_file("convert_binary", include_bytes!("data/Binary/binary"))?;
+        let dst = format!("{}/convert_binary_to_utf8", env!("CARGO_TARGET_TMPDIR"));
+        let _ = fs::remove_file(&dst);
+        let subject = file_content::convert_file(&src, &dst, Encoding::Utf8);
+
+        assert!(matches!(
+            subject,
+            Err(FileError::TextData(TextDataError::Binary))
+        ));
+        assert!(fs::metadata(&dst).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_with_progress() -> anyhow::Result<()> {
+        let bytes = FILE_CONTENT.repeat(10_000);
+        let path = super::temp_file("read_with_progress", bytes.as_bytes())?;
+        let mut progress = vec![];
+        let file = File::new_from_path_with_progress(&path, |read, total| {
+            progress.push((read, total));
+        })?;
+
+        assert_eq!(file, File::new_from_path(&path)?);
+        assert!(progress.len() > 2);
+        assert_eq!(progress[0], (0, Some(bytes.len() as u64)));
+        assert!(progress.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(progress
+            .iter()
+            .all(|(_, total)| *total == Some(bytes.len() as u64)));
+        assert_eq!(
+            progress.last(),
+            Some(&(bytes.len() as u64, Some(bytes.len() as u64)))
+        );
+
+        Ok(())
+    }
+
+    #[test_case(19, true; "at the limit")]
+    #[test_case(18, false; "over the limit")]
+    fn read_limited(max_bytes: u64, allowed: bool) -> anyhow::Result<()> {
+        let path = super::temp_file(
+            &format!("read_limited_{max_bytes}"),
+            include_bytes!("data/UTF8/unicode"),
+        )?;
+        let result = File::new_from_path_limited(&path, max_bytes);
+
+        if allowed {
+            assert_eq!(result?, File::new_from_path(&path)?);
+        } else {
+            assert!(matches!(
+                result,
+                Err(FileError::TooLarge { size: 19, limit }) if limit == max_bytes
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn read_limited_with_wrong_size() {
+        // Files in /proc are generated as they are read, so their metadata has a size of 0
+        let subject =
+            File::new_from_path_limited("/proc/self/status", 16).expect_err("Should fail");
+
+        assert!(matches!(
+            subject,
+            FileError::TooLarge {
+                size: 17,
+                limit: 16
+            }
+        ));
+    }
+
+    #[test_case("gitattributes.dat", b"Hello\0world", true; "Null bytes forced to text")]
+    #[test_case("gitattributes.bin", include_bytes!("data/UTF8/unicode"), false; "Text forced to binary")]
+    #[test_case("gitattributes.other", b"Hello\0world", false; "Null bytes detected as binary")]
+    fn read_with_gitattributes(name: &str, bytes: &[u8], is_text: bool) -> anyhow::Result<()> {
+        let path = super::temp_file(name, bytes)?;
+        let attributes = GitAttributes::parse("*.dat text\n*.bin binary\n");
+
+        let subject = File::new_with_gitattributes(&path, &attributes)?;
+
+        match subject.content {
+            FileContent::Encoded { content } if is_text => {
+                assert_eq!(content.data.as_bytes(), bytes);
+                assert_eq!(content.encoding, Encoding::Utf8);
+            }
+            FileContent::Binary { content } if !is_text => assert_eq!(content, bytes),
+            content => panic!("Unexpected content: {content:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test_case("append_utf8", include_bytes!("data/UTF8/unicode"), Encoding::Utf8; "UTF-8")]
+    #[test_case("append_utf8bom", include_bytes!("data/UTF8BOM/unicode"), Encoding::Utf8Bom; "UTF-8WithBom")]
+    #[test_case("append_utf16be", include_bytes!("data/UTF16BE/unicode"), Encoding::Utf16Be; "UTF-16BE")]
+    #[test_case("append_utf16le", include_bytes!("data/UTF16LE/unicode"), Encoding::Utf16Le; "UTF-16LE")]
+    #[test_case("append_utf32be", include_bytes!("data/UTF32BE/unicode"), Encoding::Utf32Be; "UTF-32BE")]
+    #[test_case("append_utf32le", include_bytes!("data/UTF32LE/unicode"), Encoding::Utf32Le; "UTF-32LE")]
+    fn append_encoded_content(name: &str, bytes: &[u8], encoding: Encoding) -> anyhow::Result<()> {
+        let path = super::temp_file(name, bytes)?;
+        let file = File::from_string(&path, "\nAppended 🚀".into(), encoding);
+        file.append_to_path()?;
+
+        let subject = file_content::read_to_text_data(&path)?;
+
+        assert_eq!(subject.data, format!("{FILE_CONTENT}\nAppended 🚀"));
+        assert_eq!(subject.encoding, encoding);
+
+        Ok(())
+    }
+
+    #[test]
+    fn append_to_missing_file() -> anyhow::Result<()> {
+        let path = format!("{}/append_missing_file", env!("CARGO_TARGET_TMPDIR"));
+        let _ = fs::remove_file(&path);
+        let file = File::from_string(&path, FILE_CONTENT.into(), Encoding::Utf16Le);
+        file.append_to_path()?;
+
+        assert_eq!(fs::read(&path)?, include_bytes!("data/UTF16LE/unicode"));
+
+        Ok(())
+    }
+
+    #[test_case("append_mismatch_utf16", include_bytes!("data/UTF16BE/unicode"), Encoding::Utf16Le, Encoding::Utf16Be; "different BOMs")]
+    #[test_case("append_mismatch_utf8", include_bytes!("data/UTF8/unicode"), Encoding::Utf8Bom, Encoding::Utf8; "missing BOM")]
+    fn append_mismatched_encoding(
+        name: &str,
+        bytes: &[u8],
+        encoding: Encoding,
+        expected_found: Encoding,
+    ) -> anyhow::Result<()> {
+        let path = super::temp_file(name, bytes)?;
+        let file = File::from_string(&path, "Appended".into(), encoding);
+        let subject = file.append_to_path().expect_err("Should fail");
+
+        assert!(matches!(
+            subject,
+            FileError::EncodingMismatch { expected, found, .. }
+                if expected == encoding && found == expected_found
+        ));
+        assert_eq!(fs::read(&path)?, bytes);
+
+        Ok(())
+    }
+
     #[test]
     fn save_binary_content() -> anyhow::Result<()> {
         let bytes: &[u8] = &[1, 2, 3, 0, 4, 5];
@@ -56,3 +599,250 @@ mod file_io_tests {
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "tokio"))]
+mod async_file_io_tests {
+    use file_content::{Encoding, File, FileContent, FileError, LineEnding, TextData};
+
+    const FILE_CONTENT: &str = "Hello! 你好! 🌍";
+    const UTF16LE_CONTENT: &[u8] = include_bytes!("data/UTF16LE/unicode");
+
+    #[tokio::test]
+    async fn read_encoded_content() -> anyhow::Result<()> {
+        let path = super::temp_file("async_utf16le", UTF16LE_CONTENT)?;
+        let file = File::new_from_path_async(&path).await?;
+        let expected = File {
+            path: path.into(),
+            content: FileContent::Encoded {
+                content: TextData {
+                    data: FILE_CONTENT.into(),
+                    encoding: Encoding::Utf16Le,
+                    line_ending: LineEnding::None,
+                },
+            },
+        };
+
+        assert_eq!(file, expected);
+
+        let mut bytes = vec![];
+        file.content.write(&mut bytes)?;
+
+        assert_eq!(bytes, UTF16LE_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_content_to_string() -> anyhow::Result<()> {
+        let path = super::temp_file("async_utf16le_to_string", UTF16LE_CONTENT)?;
+        let content = file_content::read_to_string_async(path).await?;
+
+        assert_eq!(content, FILE_CONTENT);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_directory() {
+        let path = env!("CARGO_TARGET_TMPDIR");
+        let subject = File::new_from_path_async(path)
+            .await
+            .expect_err("Should fail");
+
+        assert!(matches!(&subject, FileError::NotAFile(dir) if dir.as_os_str() == path));
+
+        let subject = file_content::read_to_string_async(path)
+            .await
+            .expect_err("Should fail");
+
+        assert!(matches!(&subject, FileError::NotAFile(dir) if dir.as_os_str() == path));
+    }
+
+    #[tokio::test]
+    async fn read_empty_path() {
+        let subject = File::new_from_path_async("")
+            .await
+            .expect_err("Should fail");
+
+        assert!(matches!(subject, FileError::EmptyPath));
+
+        let subject = file_content::read_to_string_async("")
+            .await
+            .expect_err("Should fail");
+
+        assert!(matches!(subject, FileError::EmptyPath));
+    }
+}
+
+#[cfg(all(test, feature = "mmap"))]
+mod mmap_file_io_tests {
+    use file_content::{Encoding, File, FileContent, LineEnding, TextData};
+    use test_case::test_case;
+
+    const FILE_CONTENT: &str = "Hello! 你好! 🌍";
+
+    #[test_case(include_bytes!("data/UTF8/unicode"), Encoding::Utf8; "UTF-8")]
+    #[test_case(include_bytes!("data/UTF16BE/unicode"), Encoding::Utf16Be; "UTF-16BE")]
+    #[test_case(include_bytes!("data/UTF32LE/unicode"), Encoding::Utf32Le; "UTF-32LE")]
+    fn read_encoded_content(bytes: &[u8], encoding: Encoding) -> anyhow::Result<()> {
+        let path = super::temp_file(&format!("mmap_{encoding}"), bytes)?;
+        let file = File::new_from_path_mmap(&path)?;
+
+        assert_eq!(file, File::new_from_path(&path)?);
+        assert_eq!(
+            file.content,
+            FileContent::Encoded {
+                content: TextData {
+                    data: FILE_CONTENT.into(),
+                    encoding,
+                    line_ending: LineEnding::None,
+                },
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_binary_content() -> anyhow::Result<()> {
+        let path = super::temp_file("mmap_binary", &[1, 2, 3, 0, 4, 5])?;
+        let file = File::new_from_path_mmap(&path)?;
+
+        assert_eq!(
+            file.content,
+            FileContent::Binary {
+                content: vec![1, 2, 3, 0, 4, 5]
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_empty_file() -> anyhow::Result<()> {
+        let path = super::temp_file("mmap_empty", &[])?;
+        let file = File::new_from_path_mmap(&path)?;
+
+        assert_eq!(
+            file.content,
+            FileContent::Encoded {
+                content: TextData {
+                    data: "".into(),
+                    encoding: Encoding::Utf8,
+                    line_ending: LineEnding::None,
+                },
+            }
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod parallel_file_io_tests {
+    use std::fs;
+
+    use file_content::{File, FileContent};
+
+    const FILE_CONTENT: &str = "Hello! 你好! 🌍";
+
+    #[test]
+    fn read_dir_parallel() -> anyhow::Result<()> {
+        let directory = format!("{}/read_dir_parallel", env!("CARGO_TARGET_TMPDIR"));
+        fs::create_dir_all(format!("{directory}/nested"))?;
+        let mut expected = vec![];
+        for i in 0..16 {
+            fs::write(format!("{directory}/text_{i:02}"), FILE_CONTENT)?;
+            expected.push(format!("text_{i:02}"));
+        }
+        fs::write(format!("{directory}/nested/text"), FILE_CONTENT)?;
+        expected.insert(0, "nested/text".into());
+
+        let mut files = File::read_dir_parallel(&directory, true)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let paths: Vec<_> = files
+            .iter()
+            .map(|file| file.path.strip_prefix(&directory))
+            .collect::<Result<_, _>>()?;
+
+        assert_eq!(
+            paths,
+            expected.iter().map(String::as_str).collect::<Vec<_>>()
+        );
+        assert!(files.iter().all(|file| matches!(
+            &file.content,
+            FileContent::Encoded { content } if content.data == FILE_CONTENT
+        )));
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "tar"))]
+mod tar_file_io_tests {
+    use file_content::{Encoding, File, FileContent, FileError, LineEnding, TextData};
+
+    const UTF16LE_CONTENT: &[u8] = include_bytes!("data/UTF16LE/unicode");
+
+    fn tar_archive() -> std::io::Result<Vec<u8>> {
+        let mut builder = tar::Builder::new(vec![]);
+        for (path, bytes) in [
+            ("docs/text.txt", UTF16LE_CONTENT),
+            ("binary", &[1, 2, 3, 0, 4, 5]),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            builder.append_data(&mut header, path, bytes)?;
+        }
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_size(0);
+        header.set_mode(0o755);
+        builder.append_data(&mut header, "docs/", std::io::empty())?;
+
+        builder.into_inner()
+    }
+
+    #[test]
+    fn read_tar() -> anyhow::Result<()> {
+        let archive = tar_archive()?;
+        let files = File::read_tar(archive.as_slice()).collect::<Result<Vec<_>, _>>()?;
+        let expected = vec![
+            File {
+                path: "docs/text.txt".into(),
+                content: FileContent::Encoded {
+                    content: TextData {
+                        data: "Hello! 你好! 🌍".into(),
+                        encoding: Encoding::Utf16Le,
+                        line_ending: LineEnding::None,
+                    },
+                },
+            },
+            File {
+                path: "binary".into(),
+                content: FileContent::Binary {
+                    content: vec![1, 2, 3, 0, 4, 5],
+                },
+            },
+        ];
+
+        assert_eq!(files, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_truncated_tar() -> anyhow::Result<()> {
+        let archive = tar_archive()?;
+        let results: Vec<_> = File::read_tar(&archive[..700]).collect();
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(FileError::Io { .. })));
+
+        Ok(())
+    }
+}